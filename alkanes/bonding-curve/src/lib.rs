@@ -0,0 +1,135 @@
+use alkane_factory_support::curve::BondingCurve;
+use alkane_factory_support::factory::MintableToken;
+use alkane_factory_support::payment::take_payment;
+use alkanes_runtime::{declare_alkane, runtime::AlkaneResponder};
+use alkanes_support::utils::overflow_error;
+use alkanes_support::context::Context;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransfer;
+use alkanes_support::{ response::CallResponse, utils::{ shift, shift_or_err } };
+use anyhow::{ anyhow, Result };
+use metashrew_support::compat::{ to_arraybuffer_layout, to_passback_ptr };
+
+#[derive(Default)]
+pub struct BondingCurveToken(());
+
+impl MintableToken for BondingCurveToken {}
+
+impl BondingCurve for BondingCurveToken {}
+
+impl BondingCurveToken {
+    /// Pulls this token out of the incoming alkanes to be burned, returning the
+    /// amount and a response forwarding everything else.
+    pub fn take_sold(&self, context: &Context) -> Result<(u128, CallResponse)> {
+        let mut response = CallResponse::default();
+        let mut sold = 0u128;
+        for transfer in context.incoming_alkanes.0.iter() {
+            if transfer.id == context.myself {
+                sold = overflow_error(sold.checked_add(transfer.value))?;
+            } else {
+                response.alkanes.0.push(transfer.clone());
+            }
+        }
+        Ok((sold, response))
+    }
+}
+
+impl AlkaneResponder for BondingCurveToken {
+    fn execute(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut inputs = context.inputs.clone();
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+        match shift_or_err(&mut inputs)? {
+            0 => {
+                self.observe_initialization()?;
+                // <curve, base price, factor>, see CURVE_* for what factor means on each curve
+                self.set_curve(shift_or_err(&mut inputs)?, shift_or_err(&mut inputs)?, shift_or_err(&mut inputs)?)?;
+                self.set_base_alkane(
+                    &context,
+                    AlkaneId {
+                        block: shift_or_err(&mut inputs)?,
+                        tx: shift_or_err(&mut inputs)?,
+                    },
+                )?;
+                let name = shift_or_err(&mut inputs)?;
+                let symbol = shift_or_err(&mut inputs)?;
+                self.validate_name_and_symbol(name, symbol)?;
+                self.set_data()?;
+                self.set_name_and_symbol(name, symbol);
+                Ok(response)
+            }
+            77 => {
+                let count = shift_or_err(&mut inputs)?;
+                if count == 0 {
+                    return Err(anyhow!("must buy at least 1 token"));
+                }
+                let cost = self.buy_cost(count)?;
+                response = take_payment(&context, &self.base_alkane()?, cost)?;
+                self.set_reserve(overflow_error(self.reserve().checked_add(cost))?);
+                response.alkanes.0.push(self.mint(&context, count)?);
+                Ok(response)
+            }
+            78 => {
+                // optional minimum refund, the sale reverts if the curve pays out less
+                let min_refund = shift(&mut inputs).unwrap_or(0);
+                let (count, mut response) = self.take_sold(&context)?;
+                if count == 0 {
+                    return Err(anyhow!("must send tokens to sell"));
+                }
+                let refund = self.sell_refund(count)?;
+                if refund < min_refund {
+                    return Err(anyhow!("refund of {} is below the minimum of {}", refund, min_refund));
+                }
+                self.set_total_supply(overflow_error(self.total_supply().checked_sub(count))?);
+                self.set_reserve(overflow_error(self.reserve().checked_sub(refund))?);
+                if refund != 0 {
+                    response.alkanes.0.push(AlkaneTransfer {
+                        id: self.base_alkane()?,
+                        value: refund,
+                    });
+                }
+                Ok(response)
+            }
+            99 => {
+                response.data = self.name().into_bytes().to_vec();
+                Ok(response)
+            }
+            100 => {
+                response.data = self.symbol().into_bytes().to_vec();
+                Ok(response)
+            }
+            101 => {
+                response.data = self.total_supply().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            102 => {
+                response.data = self.curve().to_le_bytes().to_vec();
+                response.data.extend(&self.base_price().to_le_bytes());
+                response.data.extend(&self.factor().to_le_bytes());
+                Ok(response)
+            }
+            103 => {
+                let base_alkane = self.base_alkane()?;
+                response.data = base_alkane.block.to_le_bytes().to_vec();
+                response.data.extend(&base_alkane.tx.to_le_bytes());
+                response.data.extend(&self.reserve().to_le_bytes());
+                Ok(response)
+            }
+            104 => {
+                response.data = self.buy_cost(shift_or_err(&mut inputs)?)?.to_le_bytes().to_vec();
+                Ok(response)
+            }
+            105 => {
+                response.data = self.sell_refund(shift_or_err(&mut inputs)?)?.to_le_bytes().to_vec();
+                Ok(response)
+            }
+            1000 => {
+                response.data = self.data();
+                Ok(response)
+            }
+            _ => { Err(anyhow!("unrecognized opcode")) }
+        }
+    }
+}
+
+declare_alkane!{ BondingCurveToken }
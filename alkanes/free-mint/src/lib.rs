@@ -1,19 +1,61 @@
 use alkane_factory_support::factory::MintableToken;
+use alkane_factory_support::merkle::{verify_payload, MerkleLeaf};
+use alkane_factory_support::payment;
+use alkane_factory_support::protostone::CallMessage;
+use alkanes_runtime::auth::AuthenticatedResponder;
 use alkanes_runtime::{declare_alkane, runtime::AlkaneResponder};
 use alkanes_runtime::storage::StoragePointer;
 use alkanes_support::utils::overflow_error;
-use alkanes_support::{ response::CallResponse, utils::shift_or_err };
+use alkanes_support::context::Context;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransfer;
+use alkanes_support::witness::find_witness_payload;
+use alkanes_support::{ response::CallResponse, utils::{ shift, shift_bytes32_or_err, shift_or_err } };
 use anyhow::{ anyhow, Result };
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::Transaction;
 use metashrew_support::compat::{ to_arraybuffer_layout, to_passback_ptr };
 use metashrew_support::index_pointer::KeyValuePointer;
+use metashrew_support::utils::consensus_decode;
+use std::sync::Arc;
 #[allow(unused_imports)]
 use ::{ alkanes_runtime::{ println, stdio::stdout }, std::fmt::Write };
 
 #[derive(Default)]
 pub struct MintableAlkane(());
 
+/// Everything a frontend needs to render a mint page, returned by opcode 109 as
+/// five little-endian u128 values followed by a single open flag byte.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MintStatus {
+    pub minted: u128,
+    pub cap: u128,
+    pub remaining: u128,
+    pub value_per_mint: u128,
+    pub total_supply: u128,
+    pub open: bool,
+}
+
+impl MintStatus {
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = self.minted.to_le_bytes().to_vec();
+        result.extend(&self.cap.to_le_bytes());
+        result.extend(&self.remaining.to_le_bytes());
+        result.extend(&self.value_per_mint.to_le_bytes());
+        result.extend(&self.total_supply.to_le_bytes());
+        result.push(self.open as u8);
+        result
+    }
+}
+
+pub const EMISSION_CONSTANT: u128 = 0;
+pub const EMISSION_HALVING_BY_MINTS: u128 = 1;
+pub const EMISSION_HALVING_BY_BLOCKS: u128 = 2;
+
 impl MintableToken for MintableAlkane {}
 
+impl AuthenticatedResponder for MintableAlkane {}
+
 impl MintableAlkane {
     pub fn minted_pointer(&self) -> StoragePointer {
         StoragePointer::from_keyword("/minted")
@@ -37,6 +79,78 @@ impl MintableAlkane {
     pub fn set_value_per_mint(&self, v: u128) {
         self.value_per_mint_pointer().set_value::<u128>(v);
     }
+    pub fn emission_mode_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/emission/mode")
+    }
+    pub fn emission_mode(&self) -> u128 {
+        self.emission_mode_pointer().get_value::<u128>()
+    }
+    pub fn emission_interval_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/emission/interval")
+    }
+    pub fn emission_interval(&self) -> u128 {
+        self.emission_interval_pointer().get_value::<u128>()
+    }
+    pub fn emission_start_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/emission/start")
+    }
+    pub fn emission_start(&self) -> u64 {
+        self.emission_start_pointer().get_value::<u64>()
+    }
+    pub fn set_emission_schedule(&self, mode: u128, interval: u128) -> Result<()> {
+        match mode {
+            EMISSION_CONSTANT => (),
+            EMISSION_HALVING_BY_MINTS | EMISSION_HALVING_BY_BLOCKS => {
+                if interval == 0 {
+                    return Err(anyhow!("halving interval must be greater than 0"));
+                }
+            }
+            _ => return Err(anyhow!("unrecognized emission mode: {}", mode)),
+        }
+        self.emission_mode_pointer().set_value::<u128>(mode);
+        self.emission_interval_pointer().set_value::<u128>(interval);
+        self.emission_start_pointer().set_value::<u64>(self.height());
+        Ok(())
+    }
+    /// The number of halvings applied to `value_per_mint` so far.
+    pub fn halvings(&self) -> u128 {
+        match self.emission_mode() {
+            EMISSION_HALVING_BY_MINTS => self.minted() / self.emission_interval(),
+            EMISSION_HALVING_BY_BLOCKS => {
+                (self.height().saturating_sub(self.emission_start()) as u128)
+                    / self.emission_interval()
+            }
+            _ => 0,
+        }
+    }
+    /// The amount the next mint pays out under the emission schedule.
+    pub fn current_value_per_mint(&self) -> u128 {
+        let halvings = self.halvings();
+        if halvings >= 128 {
+            0
+        } else {
+            self.value_per_mint() >> halvings
+        }
+    }
+    /// The total paid out by the next `count` mints, following halvings that
+    /// fall inside the batch.
+    pub fn value_of_mints(&self, count: u128) -> Result<u128> {
+        if self.emission_mode() != EMISSION_HALVING_BY_MINTS {
+            return overflow_error(self.current_value_per_mint().checked_mul(count));
+        }
+        let interval = self.emission_interval();
+        let mut minted = self.minted();
+        let mut left = count;
+        let mut total = 0u128;
+        while left != 0 && minted / interval < 128 {
+            let in_era = left.min(interval - minted % interval);
+            let value = overflow_error((self.value_per_mint() >> (minted / interval)).checked_mul(in_era))?;
+            total = overflow_error(total.checked_add(value))?;
+            minted = overflow_error(minted.checked_add(in_era))?;
+            left -= in_era;
+        }
+        Ok(total)
+    }
     pub fn cap_pointer(&self) -> StoragePointer {
         StoragePointer::from_keyword("/cap")
     }
@@ -46,6 +160,352 @@ impl MintableAlkane {
     pub fn set_cap(&self, v: u128) {
         self.cap_pointer().set_value::<u128>(if v == 0 { u128::MAX } else { v })
     }
+    pub fn mint_start_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/mint-start")
+    }
+    pub fn mint_start(&self) -> u64 {
+        self.mint_start_pointer().get_value::<u64>()
+    }
+    pub fn mint_end_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/mint-end")
+    }
+    pub fn mint_end(&self) -> u64 {
+        self.mint_end_pointer().get_value::<u64>()
+    }
+    pub fn set_mint_window(&self, start: u128, end: u128) -> Result<()> {
+        let start: u64 = start.try_into()?;
+        let end: u64 = end.try_into()?;
+        if end != 0 && end <= start {
+            return Err(anyhow!("mint end height must be greater than mint start height"));
+        }
+        self.mint_start_pointer().set_value::<u64>(start);
+        self.mint_end_pointer().set_value::<u64>(end);
+        Ok(())
+    }
+    pub fn max_per_tx_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/max-per-tx")
+    }
+    pub fn max_per_tx(&self) -> u128 {
+        self.max_per_tx_pointer().get_value::<u128>()
+    }
+    pub fn max_per_block_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/max-per-block")
+    }
+    pub fn max_per_block(&self) -> u128 {
+        self.max_per_block_pointer().get_value::<u128>()
+    }
+    pub fn set_rate_limits(&self, per_tx: u128, per_block: u128) {
+        self.max_per_tx_pointer().set_value::<u128>(per_tx);
+        self.max_per_block_pointer().set_value::<u128>(per_block);
+    }
+    // Calls within one block or one transaction are indexed back to back, so
+    // only the latest of each needs a count. Both slots are overwritten when a
+    // new block or transaction mints.
+    pub fn last_block_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/last-block")
+    }
+    pub fn last_block_mints_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/last-block/mints")
+    }
+    /// Mints counted so far at the current height.
+    pub fn block_mints(&self) -> u128 {
+        if self.last_block_pointer().get_value::<u64>() == self.height() {
+            self.last_block_mints_pointer().get_value::<u128>()
+        } else {
+            0
+        }
+    }
+    pub fn last_tx_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/last-tx")
+    }
+    pub fn last_tx_mints_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/last-tx/mints")
+    }
+    pub fn txid(&self) -> Result<Vec<u8>> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        Ok(tx.compute_txid().as_byte_array().to_vec())
+    }
+    /// The executing txid when a per-transaction limit applies, decoded once
+    /// per call and handed to `fillable` and `record_mints`.
+    pub fn limited_txid(&self) -> Result<Option<Vec<u8>>> {
+        if self.max_per_tx() == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(self.txid()?))
+        }
+    }
+    /// Mints counted so far in the executing transaction, identified by `txid`.
+    pub fn tx_mints(&self, txid: &[u8]) -> u128 {
+        if self.last_tx_pointer().get().as_slice() == txid {
+            self.last_tx_mints_pointer().get_value::<u128>()
+        } else {
+            0
+        }
+    }
+    /// Counts `count` mints against the per-transaction and per-block limits,
+    /// erroring if either would be exceeded. A limit of 0 is unlimited and
+    /// keeps no count. `txid` comes from `limited_txid`.
+    pub fn record_mints(&self, count: u128, txid: Option<Vec<u8>>) -> Result<()> {
+        if self.max_per_tx() == 0 && self.max_per_block() == 0 {
+            return Ok(());
+        }
+        if self.max_per_block() != 0 {
+            let block_mints = overflow_error(self.block_mints().checked_add(count))?;
+            if block_mints > self.max_per_block() {
+                return Err(anyhow!("mint limit of {} per block reached", self.max_per_block()));
+            }
+            self.last_block_pointer().set_value::<u64>(self.height());
+            self.last_block_mints_pointer().set_value::<u128>(block_mints);
+        }
+        if let Some(txid) = txid {
+            let tx_mints = overflow_error(self.tx_mints(&txid).checked_add(count))?;
+            if tx_mints > self.max_per_tx() {
+                return Err(anyhow!("mint limit of {} per transaction reached", self.max_per_tx()));
+            }
+            self.last_tx_pointer().set(Arc::new(txid));
+            self.last_tx_mints_pointer().set_value::<u128>(tx_mints);
+        }
+        Ok(())
+    }
+    pub fn price_alkane_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/price-alkane")
+    }
+    pub fn price_alkane(&self) -> Result<AlkaneId> {
+        Ok(self.price_alkane_pointer().get().as_ref().clone().try_into()?)
+    }
+    pub fn price_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/price")
+    }
+    pub fn price(&self) -> u128 {
+        self.price_pointer().get_value::<u128>()
+    }
+    /// Sets the price per mint, which must name the alkane it is paid in. A
+    /// token cannot be priced in itself.
+    pub fn set_price(&self, context: &Context, alkane: AlkaneId, v: u128) -> Result<()> {
+        if alkane == AlkaneId::default() {
+            return Err(anyhow!("a price needs the alkane it is paid in"));
+        }
+        if alkane == context.myself {
+            return Err(anyhow!("a token cannot be priced in itself"));
+        }
+        self.price_alkane_pointer().set(Arc::new(alkane.into()));
+        self.price_pointer().set_value::<u128>(v);
+        Ok(())
+    }
+    pub fn proceeds_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/proceeds")
+    }
+    pub fn proceeds(&self) -> u128 {
+        self.proceeds_pointer().get_value::<u128>()
+    }
+    pub fn set_proceeds(&self, v: u128) {
+        self.proceeds_pointer().set_value::<u128>(v);
+    }
+    /// Takes the price of `count` mints into the proceeds, see
+    /// `payment::take_payment` for what the response carries.
+    pub fn take_payment(&self, context: &Context, count: u128) -> Result<CallResponse> {
+        if self.price() == 0 {
+            return Ok(CallResponse::forward(&context.incoming_alkanes));
+        }
+        let cost = overflow_error(self.price().checked_mul(count))?;
+        let response = payment::take_payment(context, &self.price_alkane()?, cost)?;
+        self.set_proceeds(overflow_error(self.proceeds().checked_add(cost))?);
+        Ok(response)
+    }
+    /// How many of `count` requested mints fit under the cap and the
+    /// per-transaction and per-block limits. `txid` comes from `limited_txid`.
+    pub fn fillable(&self, count: u128, txid: Option<&[u8]>) -> Result<u128> {
+        let mut fill = count.min(self.cap().saturating_sub(self.minted()));
+        if self.max_per_block() != 0 {
+            fill = fill.min(self.max_per_block().saturating_sub(self.block_mints()));
+        }
+        if let Some(txid) = txid {
+            fill = fill.min(self.max_per_tx().saturating_sub(self.tx_mints(txid)));
+        }
+        Ok(fill)
+    }
+    pub fn check_cap(&self) -> Result<()> {
+        if self.minted() >= self.cap() {
+            Err(anyhow!("supply has reached cap"))
+        } else {
+            Ok(())
+        }
+    }
+    pub fn status(&self) -> MintStatus {
+        MintStatus {
+            minted: self.minted(),
+            cap: self.cap(),
+            remaining: self.cap().saturating_sub(self.minted()),
+            value_per_mint: self.current_value_per_mint(),
+            total_supply: self.total_supply(),
+            open: self.check_mint_window().is_ok()
+                && self.check_cap().is_ok()
+                && self.current_value_per_mint() != 0,
+        }
+    }
+    pub fn allowlist_length_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/allowlist/length")
+    }
+    pub fn allowlist_length(&self) -> usize {
+        self.allowlist_length_pointer().get_value::<usize>()
+    }
+    pub fn allowlist_root_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/allowlist/root")
+    }
+    pub fn allowlist_root(&self) -> Result<[u8; 32]> {
+        let root_vec: Vec<u8> = self.allowlist_root_pointer().get().as_ref().clone();
+        let root_bytes: &[u8] = root_vec.as_ref();
+        root_bytes
+            .try_into()
+            .map_err(|_| anyhow!("allowlist root bytes in storage are not of length 32"))
+    }
+    pub fn allowlist_end_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/allowlist/end")
+    }
+    pub fn allowlist_end(&self) -> u64 {
+        self.allowlist_end_pointer().get_value::<u64>()
+    }
+    pub fn set_allowlist(&self, length: u128, root: Vec<u8>, end: u128) -> Result<()> {
+        let end: u64 = end.try_into()?;
+        if end <= self.height() {
+            return Err(anyhow!("allowlist end height {} is not in the future", end));
+        }
+        self.allowlist_length_pointer().set_value::<usize>(length.try_into()?);
+        self.allowlist_root_pointer().set(Arc::new(root));
+        self.allowlist_end_pointer().set_value::<u64>(end);
+        Ok(())
+    }
+    pub fn in_allowlist_phase(&self) -> bool {
+        self.allowlist_length() != 0 && self.height() < self.allowlist_end()
+    }
+    /// Allowlist mints are counted per scriptPubKey, keyed by its sha256. A
+    /// script listed in several leaves shares one count, so its quota is the
+    /// amount of whichever leaf it proves with.
+    pub fn allowlist_mints_pointer(&self, script_pubkey: &[u8]) -> StoragePointer {
+        StoragePointer::from_keyword("/allowlist/mints/")
+            .select(&sha256::Hash::hash(script_pubkey).to_byte_array().to_vec())
+    }
+    pub fn allowlist_mints(&self, script_pubkey: &[u8]) -> u128 {
+        self.allowlist_mints_pointer(script_pubkey).get_value::<u128>()
+    }
+    /// Verifies the allowlist proof in the witness payload against the pointer
+    /// output of the calling protostone. The leaf amount is the mint quota.
+    pub fn allowlist_leaf(&self, vout: u32) -> Result<MerkleLeaf> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let message = CallMessage::decode(tx, vout)?;
+        let output = message.pointer_output()?;
+        let leaf = verify_payload(
+            find_witness_payload(&message.tx, 0).ok_or_else(|| {
+                anyhow!("alkanes-factory: witness envelope at index 0 does not contain an allowlist proof")
+            })?,
+            self.allowlist_root()?,
+            self.allowlist_length(),
+        )?;
+        if output.script_pubkey.as_bytes() != leaf.script_pubkey.as_slice() {
+            Err(anyhow!("spendable output created is not on the allowlist"))
+        } else {
+            Ok(leaf)
+        }
+    }
+    /// During the allowlist phase, limits `count` to what the caller's quota
+    /// has left and records it. Outside the phase `count` is returned as is.
+    pub fn take_allowlist_quota(&self, vout: u32, count: u128) -> Result<u128> {
+        if !self.in_allowlist_phase() {
+            return Ok(count);
+        }
+        let leaf = self.allowlist_leaf(vout)?;
+        let minted = self.allowlist_mints(&leaf.script_pubkey);
+        let fill = count.min(leaf.amount.saturating_sub(minted));
+        if fill == 0 {
+            return Err(anyhow!("allowlist quota of {} mints is used up", leaf.amount));
+        }
+        self.allowlist_mints_pointer(&leaf.script_pubkey).set_value::<u128>(minted + fill);
+        Ok(fill)
+    }
+    pub fn premine_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/premine/total")
+    }
+    pub fn premine(&self) -> u128 {
+        self.premine_pointer().get_value::<u128>()
+    }
+    pub fn premine_released_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/premine/released")
+    }
+    pub fn premine_released(&self) -> u128 {
+        self.premine_released_pointer().get_value::<u128>()
+    }
+    pub fn premine_start_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/premine/start")
+    }
+    pub fn premine_start(&self) -> u64 {
+        self.premine_start_pointer().get_value::<u64>()
+    }
+    pub fn premine_end_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/premine/end")
+    }
+    pub fn premine_end(&self) -> u64 {
+        self.premine_end_pointer().get_value::<u64>()
+    }
+    pub fn set_premine_vesting(&self, total: u128, start: u128, end: u128) -> Result<()> {
+        let start: u64 = start.try_into()?;
+        let end: u64 = end.try_into()?;
+        if end <= start {
+            return Err(anyhow!("premine vesting end height must be greater than start height"));
+        }
+        self.premine_pointer().set_value::<u128>(total);
+        self.premine_start_pointer().set_value::<u64>(start);
+        self.premine_end_pointer().set_value::<u64>(end);
+        Ok(())
+    }
+    /// The part of the locked premine unlocked so far, released linearly
+    /// between the vesting start and end heights.
+    pub fn premine_vested(&self) -> Result<u128> {
+        let (start, end, height) = (self.premine_start(), self.premine_end(), self.height());
+        if height >= end {
+            Ok(self.premine())
+        } else if height <= start {
+            Ok(0)
+        } else {
+            Ok(overflow_error(self.premine().checked_mul((height - start) as u128))?
+                / ((end - start) as u128))
+        }
+    }
+    /// Rejects initialize parameters that would leave the token unmintable or
+    /// unreadable.
+    pub fn validate_parameters(
+        &self,
+        token_units: u128,
+        value_per_mint: u128,
+        cap: u128,
+        name: u128,
+        symbol: u128,
+    ) -> Result<()> {
+        if value_per_mint == 0 {
+            return Err(anyhow!("value per mint must be greater than 0"));
+        }
+        if cap != 0 {
+            if let Some(supply) = cap.checked_mul(value_per_mint) {
+                if token_units > supply {
+                    return Err(anyhow!(
+                        "premine of {} exceeds the {} supply implied by the cap",
+                        token_units,
+                        supply
+                    ));
+                }
+            }
+        }
+        self.validate_name_and_symbol(name, symbol)
+    }
+    pub fn check_mint_window(&self) -> Result<()> {
+        let height = self.height();
+        if height < self.mint_start() {
+            Err(anyhow!("minting opens at height {}", self.mint_start()))
+        } else if self.mint_end() != 0 && height >= self.mint_end() {
+            Err(anyhow!("minting closed at height {}", self.mint_end()))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl AlkaneResponder for MintableAlkane {
@@ -55,22 +515,114 @@ impl AlkaneResponder for MintableAlkane {
         let mut response = CallResponse::forward(&context.incoming_alkanes);
         match shift_or_err(&mut inputs)? {
             0 => {
+                self.observe_initialization()?;
                 let token_units = shift_or_err(&mut inputs)?;
-                self.set_value_per_mint(shift_or_err(&mut inputs)?);
-                self.set_cap(shift_or_err(&mut inputs)?); // use 0 for an unlimited supply
+                let value_per_mint = shift_or_err(&mut inputs)?;
+                let cap = shift_or_err(&mut inputs)?; // use 0 for an unlimited supply
+                let name = shift_or_err(&mut inputs)?;
+                let symbol = shift_or_err(&mut inputs)?;
+                self.validate_parameters(token_units, value_per_mint, cap, name, symbol)?;
+                self.set_value_per_mint(value_per_mint);
+                self.set_cap(cap);
                 self.set_data()?;
-                self.set_name_and_symbol(shift_or_err(&mut inputs)?, shift_or_err(&mut inputs)?);
-                response.alkanes.0.push(self.mint(&context, token_units)?);
+                self.set_name_and_symbol(name, symbol);
+                // optional mint window, 0 leaves either side unbounded
+                self.set_mint_window(shift(&mut inputs).unwrap_or(0), shift(&mut inputs).unwrap_or(0))?;
+                // optional rate limits, 0 is unlimited
+                self.set_rate_limits(shift(&mut inputs).unwrap_or(0), shift(&mut inputs).unwrap_or(0));
+                // optional price per mint as <block, tx, amount>, the treasury auth token is returned to the deployer
+                let price_alkane = AlkaneId {
+                    block: shift(&mut inputs).unwrap_or(0),
+                    tx: shift(&mut inputs).unwrap_or(0),
+                };
+                let price = shift(&mut inputs).unwrap_or(0);
+                if price != 0 {
+                    self.set_price(&context, price_alkane, price)?;
+                }
+                // optional emission schedule as <mode, interval>, see EMISSION_* for modes
+                self.set_emission_schedule(shift(&mut inputs).unwrap_or(0), shift(&mut inputs).unwrap_or(0))?;
+                // optional allowlist phase as <length, root, end height>, a length of 0 skips it
+                let allowlist_length = shift(&mut inputs).unwrap_or(0);
+                if allowlist_length != 0 {
+                    self.set_allowlist(allowlist_length, shift_bytes32_or_err(&mut inputs)?, shift_or_err(&mut inputs)?)?;
+                }
+                // optional premine vesting as <start, end>, the premine is then minted to the
+                // contract itself and released to the auth token holder
+                let premine_start = shift(&mut inputs).unwrap_or(0);
+                let premine_end = shift(&mut inputs).unwrap_or(0);
+                if premine_end != 0 {
+                    self.set_premine_vesting(token_units, premine_start, premine_end)?;
+                    self.increase_total_supply(token_units)?;
+                } else {
+                    response.alkanes.0.push(self.mint(&context, token_units)?);
+                }
+                // a single auth token both withdraws the proceeds (80) and releases the
+                // premine (81), whoever holds it controls both
+                if price != 0 || premine_end != 0 {
+                    response.alkanes.0.push(self.deploy_auth_token(1)?);
+                }
                 Ok(response)
             }
             77 => {
-                response.alkanes.0.push(self.mint(&context, self.value_per_mint())?);
+                self.check_mint_window()?;
+                self.check_cap()?;
+                let value = self.current_value_per_mint();
+                if value == 0 {
+                    return Err(anyhow!("emission schedule has ended"));
+                }
+                self.take_allowlist_quota(context.vout, 1)?;
+                self.record_mints(1, self.limited_txid()?)?;
+                response = self.take_payment(&context, 1)?;
+                response.alkanes.0.push(self.mint(&context, value)?);
                 self.increment_mint()?;
-                if self.minted() > self.cap() {
-                    Err(anyhow!("supply has reached cap"))
-                } else {
-                    Ok(response)
+                Ok(response)
+            }
+            78 => {
+                self.check_mint_window()?;
+                self.check_cap()?;
+                let txid = self.limited_txid()?;
+                let count = self.fillable(shift_or_err(&mut inputs)?, txid.as_deref())?;
+                if count == 0 {
+                    return Err(anyhow!("no mints left to fill"));
+                }
+                let count = self.take_allowlist_quota(context.vout, count)?;
+                let value = self.value_of_mints(count)?;
+                if value == 0 {
+                    return Err(anyhow!("emission schedule has ended"));
+                }
+                self.record_mints(count, txid)?;
+                response = self.take_payment(&context, count)?;
+                response.alkanes.0.push(self.mint(&context, value)?);
+                self.set_minted(overflow_error(self.minted().checked_add(count))?);
+                Ok(response)
+            }
+            80 => {
+                self.only_owner()?;
+                let proceeds = self.proceeds();
+                if proceeds == 0 {
+                    return Err(anyhow!("no proceeds to withdraw"));
                 }
+                self.set_proceeds(0);
+                response.alkanes.0.push(AlkaneTransfer {
+                    id: self.price_alkane()?,
+                    value: proceeds,
+                });
+                Ok(response)
+            }
+            81 => {
+                self.only_owner()?;
+                let vested = self.premine_vested()?;
+                let value = overflow_error(vested.checked_sub(self.premine_released()))?;
+                if value == 0 {
+                    return Err(anyhow!("no premine has vested since the last release"));
+                }
+                self.premine_released_pointer().set_value::<u128>(vested);
+                // already counted in the total supply at initialize
+                response.alkanes.0.push(AlkaneTransfer {
+                    id: context.myself.clone(),
+                    value,
+                });
+                Ok(response)
             }
             99 => {
                 response.data = self.name().into_bytes().to_vec();
@@ -93,7 +645,49 @@ impl AlkaneResponder for MintableAlkane {
                 Ok(response)
             }
             104 => {
-                response.data = self.value_per_mint().to_le_bytes().to_vec();
+                response.data = self.current_value_per_mint().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            105 => {
+                response.data = self.mint_start().to_le_bytes().to_vec();
+                response.data.extend(&self.mint_end().to_le_bytes());
+                Ok(response)
+            }
+            106 => {
+                response.data = self.max_per_tx().to_le_bytes().to_vec();
+                response.data.extend(&self.max_per_block().to_le_bytes());
+                response.data.extend(&self.block_mints().to_le_bytes());
+                Ok(response)
+            }
+            107 => {
+                let price_alkane = self.price_alkane().unwrap_or_default();
+                response.data = price_alkane.block.to_le_bytes().to_vec();
+                response.data.extend(&price_alkane.tx.to_le_bytes());
+                response.data.extend(&self.price().to_le_bytes());
+                response.data.extend(&self.proceeds().to_le_bytes());
+                Ok(response)
+            }
+            108 => {
+                response.data = self.emission_mode().to_le_bytes().to_vec();
+                response.data.extend(&self.emission_interval().to_le_bytes());
+                response.data.extend(&self.value_per_mint().to_le_bytes());
+                Ok(response)
+            }
+            109 => {
+                response.data = self.status().serialize();
+                Ok(response)
+            }
+            110 => {
+                response.data = self.allowlist_root_pointer().get().as_ref().clone();
+                response.data.extend(&self.allowlist_end().to_le_bytes());
+                response.data.extend(&(self.allowlist_length() as u64).to_le_bytes());
+                Ok(response)
+            }
+            111 => {
+                response.data = self.premine().to_le_bytes().to_vec();
+                response.data.extend(&self.premine_released().to_le_bytes());
+                response.data.extend(&self.premine_start().to_le_bytes());
+                response.data.extend(&self.premine_end().to_le_bytes());
                 Ok(response)
             }
             1000 => {
@@ -0,0 +1,124 @@
+use alkane_factory_support::protostone::MessageError;
+use alkanes_support::id::AlkaneId;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DistributorError {
+    AlreadyInitialized,
+    NoDeposit,
+    InvalidLength(u128),
+    UnknownEpoch(u128),
+    InvalidVestingSchedule { start: u64, end: u64 },
+    InvalidHeight(u128),
+    DeadlineNotInFuture(u64),
+    DeadlineWithoutOwner,
+    VestingEndsAfterDeadline { end: u64, deadline: u64 },
+    Message(MessageError),
+    PointerIsNotPayoutProtostone(u32),
+    MissingWitnessPayload,
+    OutputMismatch,
+    PayoutEdictCount { edicts: usize, leaves: usize },
+    NoPayoutEdict(usize),
+    UnknownAsset(AlkaneId),
+    AlreadyClaimed(usize),
+    NothingClaimable(usize),
+    ExceedsClaimable { index: usize, requested: u128, claimable: u128 },
+    InsufficientBudget { epoch: u32, asset: AlkaneId },
+    DeadlinePassed(u64),
+    NoDeadline,
+    DeadlineNotReached(u64),
+    CorruptStorage(&'static str),
+    Overflow,
+    UnrecognizedOpcode(u128),
+}
+
+impl fmt::Display for DistributorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyInitialized => write!(f, "already initialized"),
+            Self::NoDeposit => write!(f, "must send at least 1 alkane to lock for distribution"),
+            Self::InvalidLength(v) => write!(f, "merkle tree length {} is not valid", v),
+            Self::UnknownEpoch(epoch) => write!(f, "epoch {} does not exist", epoch),
+            Self::InvalidVestingSchedule { start, end } => write!(
+                f,
+                "vesting end height {} must be greater than start height {}",
+                end, start
+            ),
+            Self::InvalidHeight(v) => write!(f, "{} is not a valid block height", v),
+            Self::DeadlineNotInFuture(deadline) => {
+                write!(f, "claim deadline at height {} is not in the future", deadline)
+            }
+            Self::DeadlineWithoutOwner => write!(
+                f,
+                "a claim deadline needs auth token units to sweep the distribution with"
+            ),
+            Self::VestingEndsAfterDeadline { end, deadline } => write!(
+                f,
+                "vesting end height {} is after the claim deadline at height {}",
+                end, deadline
+            ),
+            Self::Message(e) => write!(f, "{}", e),
+            Self::PointerIsNotPayoutProtostone(pointer) => write!(
+                f,
+                "pointer {} must target a later protostone carrying the payout edicts",
+                pointer
+            ),
+            Self::MissingWitnessPayload => write!(
+                f,
+                "alkanes-factory: witness envelope at index 0 does not contain data"
+            ),
+            Self::OutputMismatch => write!(f, "spendable output created does not match proof"),
+            Self::PayoutEdictCount { edicts, leaves } => write!(
+                f,
+                "payout protostone must contain exactly one edict per leaf, found {} edicts for {} leaves",
+                edicts, leaves
+            ),
+            Self::NoPayoutEdict(index) => {
+                write!(f, "no payout edict matches leaf at index {}", index)
+            }
+            Self::UnknownAsset(asset) => write!(
+                f,
+                "alkane {}:{} is not part of this distribution",
+                asset.block, asset.tx
+            ),
+            Self::AlreadyClaimed(index) => {
+                write!(f, "leaf at index {} has already been claimed", index)
+            }
+            Self::NothingClaimable(index) => {
+                write!(f, "nothing has vested for leaf at index {}", index)
+            }
+            Self::ExceedsClaimable {
+                index,
+                requested,
+                claimable,
+            } => write!(
+                f,
+                "requested {} for leaf at index {} but only {} is claimable",
+                requested, index, claimable
+            ),
+            Self::InsufficientBudget { epoch, asset } => write!(
+                f,
+                "epoch {} does not hold enough of alkane {}:{} for this claim",
+                epoch, asset.block, asset.tx
+            ),
+            Self::DeadlinePassed(deadline) => {
+                write!(f, "claim deadline at height {} has passed", deadline)
+            }
+            Self::NoDeadline => write!(f, "distribution has no deadline and cannot be swept"),
+            Self::DeadlineNotReached(deadline) => {
+                write!(f, "claim deadline at height {} has not passed yet", deadline)
+            }
+            Self::CorruptStorage(field) => write!(f, "{} in storage is malformed", field),
+            Self::Overflow => write!(f, "overflow error"),
+            Self::UnrecognizedOpcode(opcode) => write!(f, "opcode {} not recognized", opcode),
+        }
+    }
+}
+
+impl std::error::Error for DistributorError {}
+
+impl From<MessageError> for DistributorError {
+    fn from(e: MessageError) -> Self {
+        Self::Message(e)
+    }
+}
@@ -1,30 +1,46 @@
+pub mod error;
+
+use crate::error::DistributorError;
+use alkane_factory_support::merkle::{verify_multi_payload, verify_payload, MerkleLeaf};
+use alkane_factory_support::protostone::CallMessage;
+use alkanes_runtime::auth::AuthenticatedResponder;
 use alkanes_runtime::{declare_alkane, runtime::AlkaneResponder};
 use alkanes_runtime::storage::StoragePointer;
 use alkanes_support::{
     id::AlkaneId,
     parcel::AlkaneTransfer,
     response::CallResponse,
-    utils::{shift_bytes32_or_err, shift_or_err},
+    utils::{shift, shift_bytes32_or_err, shift_or_err},
     witness::find_witness_payload,
 };
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use bitcoin::Transaction;
+use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
 use metashrew_support::index_pointer::KeyValuePointer;
-use metashrew_support::{
-    compat::{to_arraybuffer_layout, to_passback_ptr},
-    utils::{consume_exact, consume_sized_int, consume_to_end},
-};
-use ordinals::{Artifact, Runestone};
-use protorune_support::{protostone::Protostone, utils::consensus_decode};
-use rs_merkle::{algorithms::Sha256, Hasher, MerkleProof};
-use std::io::Cursor;
+use protorune_support::utils::consensus_decode;
 use std::sync::Arc;
 
 #[derive(Default)]
 struct MerkleDistributor(());
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub epoch: u32,
+    pub index: usize,
+    pub amount: u128,
+    pub asset: AlkaneId,
+    /// The amount to release, or everything claimable when unset.
+    pub requested: Option<u128>,
+}
+
 pub fn overflow_error(v: Option<u128>) -> Result<u128> {
-    v.ok_or("").map_err(|_| anyhow!("overflow error"))
+    Ok(v.ok_or(DistributorError::Overflow)?)
+}
+
+/// Reads an optional block height input, treating a missing value as 0.
+pub fn shift_height(inputs: &mut Vec<u128>) -> Result<u64> {
+    let v = shift(inputs).unwrap_or(0);
+    Ok(v.try_into().map_err(|_| DistributorError::InvalidHeight(v))?)
 }
 
 pub fn sub_fees(v: u128) -> Result<u128> {
@@ -32,89 +48,353 @@ pub fn sub_fees(v: u128) -> Result<u128> {
 }
 
 impl MerkleDistributor {
-    pub fn verify_output(&self, vout: u32) -> Result<u128> {
+    /// Decodes the transaction and the protostone executing this call, which
+    /// must carry no edicts of its own.
+    pub fn message(&self, vout: u32) -> Result<CallMessage> {
         let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
-        if let Some(Artifact::Runestone(ref runestone)) = Runestone::decipher(&tx) {
-            let protostones = Protostone::from_runestone(runestone)?;
-            let message = &protostones[(vout as usize) - tx.output.len() - 1];
-            if message.edicts.len() != 0 {
-                panic!("message cannot contain edicts, only a pointer")
-            }
-            let pointer = message
-                .pointer
-                .ok_or("")
-                .map_err(|_| anyhow!("no pointer in message"))?;
-            if pointer as usize >= tx.output.len() {
-                panic!("pointer cannot be a protomessage");
-            }
-            let mut cursor: Cursor<Vec<u8>> =
-                Cursor::<Vec<u8>>::new(find_witness_payload(&tx, 0).ok_or_else(|| {
-                    anyhow!("alkanes-factory: witness envelope at index 0 does not contain data")
-                })?);
-            let leaf = consume_exact(&mut cursor, 40)?;
-            let leaf_hash = Sha256::hash(&leaf);
-            let proof = consume_to_end(&mut cursor)?;
-            let mut leaf_cursor = Cursor::new(leaf.clone());
-            let p2sh = consume_exact(&mut leaf_cursor, 20)?;
-            let index = consume_sized_int::<u32>(&mut leaf_cursor)? as usize;
-            let amount = consume_sized_int::<u128>(&mut leaf_cursor)?;
-            if MerkleProof::<Sha256>::try_from(proof)?.verify(
-                self.root()?,
-                &[index],
-                &[leaf_hash],
-                self.length(),
-            ) {
-                if tx.output[(vout as usize) as usize]
-                    .script_pubkey
-                    .clone()
-                    .into_bytes()
-                    .to_vec()
-                    != p2sh
-                {
-                    Err(anyhow!("spendable output created does not match proof"))
+        let message = CallMessage::decode(tx, vout).map_err(DistributorError::from)?;
+        message.check_no_edicts().map_err(DistributorError::from)?;
+        Ok(message)
+    }
+    pub fn witness_payload(&self, tx: &Transaction) -> Result<Vec<u8>> {
+        Ok(find_witness_payload(tx, 0).ok_or(DistributorError::MissingWitnessPayload)?)
+    }
+    pub fn leaf_asset(&self, leaf: &MerkleLeaf) -> Result<AlkaneId> {
+        match leaf.asset.clone() {
+            Some(asset) => {
+                if self.alkanes()?.contains(&asset) {
+                    Ok(asset)
                 } else {
-                    Ok(amount)
+                    Err(DistributorError::UnknownAsset(asset).into())
                 }
-            } else {
-                Err(anyhow!("proof verification failure"))
             }
+            None => self.alkane(),
+        }
+    }
+    pub fn verify_output(&self, vout: u32, epoch: u32) -> Result<Claim> {
+        let message = self.message(vout)?;
+        let output = message.pointer_output().map_err(DistributorError::from)?;
+        let leaf = verify_payload(
+            self.witness_payload(&message.tx)?,
+            self.root(epoch)?,
+            self.length(epoch),
+        )?;
+        if output.script_pubkey.as_bytes() != leaf.script_pubkey.as_slice() {
+            Err(DistributorError::OutputMismatch.into())
         } else {
-            Err(anyhow!("runestone decipher failed"))
+            Ok(Claim {
+                epoch,
+                index: leaf.index as usize,
+                amount: leaf.amount,
+                asset: self.leaf_asset(&leaf)?,
+                requested: None,
+            })
         }
     }
-    pub fn length_pointer(&self) -> StoragePointer {
-        StoragePointer::from_keyword("/length")
+    /// Verifies a batch claim. The message must point at a later protostone
+    /// whose edicts pay each proven leaf to an output carrying its
+    /// scriptPubKey, one edict per leaf. Each claim requests the amount of
+    /// its matching edict.
+    pub fn verify_outputs(&self, vout: u32, epoch: u32) -> Result<Vec<Claim>> {
+        let message = self.message(vout)?;
+        let tx = &message.tx;
+        let pointer = message.pointer().map_err(DistributorError::from)?;
+        let target = (pointer as usize)
+            .checked_sub(tx.output.len() + 1)
+            .filter(|target| *target > message.position)
+            .and_then(|target| message.protostones.get(target))
+            .ok_or(DistributorError::PointerIsNotPayoutProtostone(pointer))?;
+        let leaves = verify_multi_payload(
+            self.witness_payload(tx)?,
+            self.root(epoch)?,
+            self.length(epoch),
+        )?;
+        if target.edicts.len() != leaves.len() {
+            return Err(DistributorError::PayoutEdictCount {
+                edicts: target.edicts.len(),
+                leaves: leaves.len(),
+            }
+            .into());
+        }
+        let mut used = vec![false; target.edicts.len()];
+        leaves
+            .into_iter()
+            .map(|leaf| -> Result<Claim> {
+                let asset = self.leaf_asset(&leaf)?;
+                let found = target
+                    .edicts
+                    .iter()
+                    .enumerate()
+                    .position(|(i, edict)| {
+                        !used[i]
+                            && edict.id.block == asset.block
+                            && edict.id.tx == asset.tx
+                            && (edict.output as usize) < tx.output.len()
+                            && tx.output[edict.output as usize].script_pubkey.as_bytes()
+                                == leaf.script_pubkey.as_slice()
+                    })
+                    .ok_or(DistributorError::NoPayoutEdict(leaf.index as usize))?;
+                used[found] = true;
+                Ok(Claim {
+                    epoch,
+                    index: leaf.index as usize,
+                    amount: leaf.amount,
+                    asset,
+                    requested: Some(target.edicts[found].amount),
+                })
+            })
+            .collect::<Result<Vec<Claim>>>()
     }
-    pub fn root_pointer(&self) -> StoragePointer {
-        StoragePointer::from_keyword("/root")
+    /// Records a claim against a leaf and returns the value paid out.
+    pub fn claim(&self, claim: &Claim) -> Result<u128> {
+        let (epoch, index, amount) = (claim.epoch, claim.index, claim.amount);
+        if self.is_claimed(epoch, index) {
+            return Err(DistributorError::AlreadyClaimed(index).into());
+        }
+        let claimable = self.claimable(epoch, index, amount)?;
+        let value = claim.requested.unwrap_or(claimable);
+        if value == 0 {
+            return Err(DistributorError::NothingClaimable(index).into());
+        }
+        if value > claimable {
+            return Err(DistributorError::ExceedsClaimable {
+                index,
+                requested: value,
+                claimable,
+            }
+            .into());
+        }
+        let claimed = overflow_error(self.claimed_amount(epoch, index).checked_add(value))?;
+        self.set_claimed_amount(epoch, index, claimed);
+        if claimed == amount {
+            self.set_claimed(epoch, index);
+        }
+        self.decrease_budget(epoch, &claim.asset, value)?;
+        self.decrease_remaining(&claim.asset, value)?;
+        Ok(value)
+    }
+    pub fn epochs_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/epochs")
     }
-    pub fn set_length(&self, v: usize) {
-        self.length_pointer().set_value::<usize>(v);
+    pub fn epochs(&self) -> u32 {
+        self.epochs_pointer().get_value::<u32>()
     }
-    pub fn set_root(&self, v: Vec<u8>) {
-        self.root_pointer().set(Arc::new(v))
+    pub fn epoch_pointer(&self, epoch: u32) -> StoragePointer {
+        StoragePointer::from_keyword("/epoch/").select(&epoch.to_le_bytes().to_vec())
     }
-    pub fn length(&self) -> usize {
-        self.length_pointer().get_value::<usize>()
+    /// Appends a new epoch with its own root, length and claim state,
+    /// funded by `deposits`, returning its number.
+    pub fn add_epoch(&self, length: u128, root: Vec<u8>, deposits: &[AlkaneTransfer]) -> Result<u32> {
+        let epoch = self.epochs();
+        match usize::try_from(length) {
+            Ok(v) if v != 0 => self.set_length(epoch, v),
+            _ => return Err(DistributorError::InvalidLength(length).into()),
+        }
+        self.set_root(epoch, root);
+        if deposits.iter().all(|transfer| transfer.value == 0) {
+            return Err(DistributorError::NoDeposit.into());
+        }
+        for transfer in deposits {
+            self.add_alkane(transfer.id.clone())?;
+            self.increase_remaining(&transfer.id, transfer.value)?;
+            self.increase_budget(epoch, &transfer.id, transfer.value)?;
+        }
+        let next = epoch.checked_add(1).ok_or(DistributorError::Overflow)?;
+        self.epochs_pointer().set_value::<u32>(next);
+        Ok(epoch)
     }
-    pub fn root(&self) -> Result<[u8; 32]> {
-        let root_vec: Vec<u8> = self.root_pointer().get().as_ref().clone();
+    pub fn length_pointer(&self, epoch: u32) -> StoragePointer {
+        self.epoch_pointer(epoch).keyword("/length")
+    }
+    pub fn root_pointer(&self, epoch: u32) -> StoragePointer {
+        self.epoch_pointer(epoch).keyword("/root")
+    }
+    pub fn set_length(&self, epoch: u32, v: usize) {
+        self.length_pointer(epoch).set_value::<usize>(v);
+    }
+    pub fn set_root(&self, epoch: u32, v: Vec<u8>) {
+        self.root_pointer(epoch).set(Arc::new(v))
+    }
+    pub fn length(&self, epoch: u32) -> usize {
+        self.length_pointer(epoch).get_value::<usize>()
+    }
+    pub fn root(&self, epoch: u32) -> Result<[u8; 32]> {
+        let root_vec: Vec<u8> = self.root_pointer(epoch).get().as_ref().clone();
         let root_bytes: &[u8] = root_vec.as_ref();
         root_bytes
             .try_into()
-            .map_err(|_| anyhow!("root bytes in storage are not of length 32"))
+            .map_err(|_| DistributorError::CorruptStorage("root").into())
+    }
+    pub fn claimed_pointer(&self, epoch: u32) -> StoragePointer {
+        self.epoch_pointer(epoch).keyword("/claimed/")
     }
-    pub fn alkane_pointer(&self) -> StoragePointer {
-        StoragePointer::from_keyword("/alkane")
+    pub fn claimed_word_pointer(&self, epoch: u32, index: usize) -> StoragePointer {
+        self.claimed_pointer(epoch)
+            .select(&((index / 128) as u64).to_le_bytes().to_vec())
     }
+    pub fn is_claimed(&self, epoch: u32, index: usize) -> bool {
+        self.claimed_word_pointer(epoch, index).get_value::<u128>() & (1u128 << (index % 128))
+            != 0
+    }
+    pub fn set_claimed(&self, epoch: u32, index: usize) {
+        let mut pointer = self.claimed_word_pointer(epoch, index);
+        let word = pointer.get_value::<u128>();
+        pointer.set_value::<u128>(word | (1u128 << (index % 128)));
+    }
+    pub fn alkanes_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/alkanes")
+    }
+    pub fn alkanes(&self) -> Result<Vec<AlkaneId>> {
+        self.alkanes_pointer()
+            .get()
+            .chunks(32)
+            .map(|v| -> Result<AlkaneId> {
+                v.to_vec()
+                    .try_into()
+                    .map_err(|_| DistributorError::CorruptStorage("alkanes").into())
+            })
+            .collect::<Result<Vec<AlkaneId>>>()
+    }
+    /// The first alkane locked at initialize, paid out by leaves that do not
+    /// carry an asset id.
     pub fn alkane(&self) -> Result<AlkaneId> {
-        Ok(self.alkane_pointer().get().as_ref().clone().try_into()?)
+        self.alkanes()?
+            .into_iter()
+            .next()
+            .ok_or(DistributorError::CorruptStorage("alkanes").into())
+    }
+    pub fn add_alkane(&self, v: AlkaneId) -> Result<()> {
+        if !self.alkanes()?.contains(&v) {
+            let mut alkanes: Vec<u8> = self.alkanes_pointer().get().as_ref().clone();
+            alkanes.extend(<AlkaneId as Into<Vec<u8>>>::into(v));
+            self.alkanes_pointer().set(Arc::new(alkanes));
+        }
+        Ok(())
+    }
+    pub fn remaining_pointer(&self, asset: &AlkaneId) -> StoragePointer {
+        StoragePointer::from_keyword("/remaining/")
+            .select(&<AlkaneId as Into<Vec<u8>>>::into(asset.clone()))
+    }
+    pub fn remaining(&self, asset: &AlkaneId) -> u128 {
+        self.remaining_pointer(asset).get_value::<u128>()
+    }
+    pub fn set_remaining(&self, asset: &AlkaneId, v: u128) {
+        self.remaining_pointer(asset).set_value::<u128>(v);
+    }
+    pub fn increase_remaining(&self, asset: &AlkaneId, v: u128) -> Result<()> {
+        self.set_remaining(asset, overflow_error(self.remaining(asset).checked_add(v))?);
+        Ok(())
+    }
+    pub fn decrease_remaining(&self, asset: &AlkaneId, v: u128) -> Result<()> {
+        self.set_remaining(asset, overflow_error(self.remaining(asset).checked_sub(v))?);
+        Ok(())
+    }
+    /// What is left of the deposits made for `epoch`. Claims are paid out of
+    /// their own epoch's budget, so one epoch can never drain another.
+    pub fn budget_pointer(&self, epoch: u32, asset: &AlkaneId) -> StoragePointer {
+        self.epoch_pointer(epoch)
+            .keyword("/budget/")
+            .select(&<AlkaneId as Into<Vec<u8>>>::into(asset.clone()))
+    }
+    pub fn budget(&self, epoch: u32, asset: &AlkaneId) -> u128 {
+        self.budget_pointer(epoch, asset).get_value::<u128>()
+    }
+    pub fn increase_budget(&self, epoch: u32, asset: &AlkaneId, v: u128) -> Result<()> {
+        let budget = overflow_error(self.budget(epoch, asset).checked_add(v))?;
+        self.budget_pointer(epoch, asset).set_value::<u128>(budget);
+        Ok(())
     }
-    pub fn set_alkane(&self, v: AlkaneId) {
-        self.alkane_pointer().set(Arc::<Vec<u8>>::new(v.into()));
+    pub fn decrease_budget(&self, epoch: u32, asset: &AlkaneId, v: u128) -> Result<()> {
+        let budget = self
+            .budget(epoch, asset)
+            .checked_sub(v)
+            .ok_or_else(|| DistributorError::InsufficientBudget {
+                epoch,
+                asset: asset.clone(),
+            })?;
+        self.budget_pointer(epoch, asset).set_value::<u128>(budget);
+        Ok(())
+    }
+    pub fn deadline_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/deadline")
+    }
+    pub fn deadline(&self) -> u64 {
+        self.deadline_pointer().get_value::<u64>()
+    }
+    pub fn set_deadline(&self, v: u64) -> Result<()> {
+        if v != 0 && v <= self.height() {
+            return Err(DistributorError::DeadlineNotInFuture(v).into());
+        }
+        self.deadline_pointer().set_value::<u64>(v);
+        Ok(())
+    }
+    pub fn claimed_amount_pointer(&self, epoch: u32, index: usize) -> StoragePointer {
+        self.epoch_pointer(epoch)
+            .keyword("/claimed-amount/")
+            .select(&(index as u64).to_le_bytes().to_vec())
+    }
+    pub fn claimed_amount(&self, epoch: u32, index: usize) -> u128 {
+        self.claimed_amount_pointer(epoch, index).get_value::<u128>()
+    }
+    pub fn set_claimed_amount(&self, epoch: u32, index: usize, v: u128) {
+        self.claimed_amount_pointer(epoch, index).set_value::<u128>(v);
+    }
+    pub fn vesting_start_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/vesting/start")
+    }
+    pub fn vesting_end_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/vesting/end")
+    }
+    pub fn vesting_start(&self) -> u64 {
+        self.vesting_start_pointer().get_value::<u64>()
+    }
+    pub fn vesting_end(&self) -> u64 {
+        self.vesting_end_pointer().get_value::<u64>()
+    }
+    /// Sets the vesting schedule, which must end by the deadline so that no
+    /// part of a leaf is left to the sweep.
+    pub fn set_vesting(&self, start: u64, end: u64) -> Result<()> {
+        if end != 0 && end <= start {
+            return Err(DistributorError::InvalidVestingSchedule { start, end }.into());
+        }
+        let deadline = self.deadline();
+        if deadline != 0 && end > deadline {
+            return Err(DistributorError::VestingEndsAfterDeadline { end, deadline }.into());
+        }
+        self.vesting_start_pointer().set_value::<u64>(start);
+        self.vesting_end_pointer().set_value::<u64>(end);
+        Ok(())
+    }
+    pub fn vested(&self, amount: u128) -> Result<u128> {
+        let (start, end, height) = (self.vesting_start(), self.vesting_end(), self.height());
+        if end == 0 || height >= end {
+            Ok(amount)
+        } else if height <= start {
+            Ok(0)
+        } else {
+            Ok(overflow_error(amount.checked_mul((height - start) as u128))?
+                / ((end - start) as u128))
+        }
+    }
+    pub fn claimable(&self, epoch: u32, index: usize, amount: u128) -> Result<u128> {
+        overflow_error(self.vested(amount)?.checked_sub(self.claimed_amount(epoch, index)))
+    }
+    /// Reads an optional epoch input, defaulting to the initial epoch.
+    pub fn shift_epoch(&self, inputs: &mut Vec<u128>) -> Result<u32> {
+        let v = shift(inputs).unwrap_or(0);
+        match u32::try_from(v) {
+            Ok(epoch) if epoch < self.epochs() => Ok(epoch),
+            _ => Err(DistributorError::UnknownEpoch(v).into()),
+        }
+    }
+    pub fn is_expired(&self) -> bool {
+        let deadline = self.deadline();
+        deadline != 0 && self.height() >= deadline
     }
 }
 
+impl AuthenticatedResponder for MerkleDistributor {}
+
 impl AlkaneResponder for MerkleDistributor {
     fn execute(&self) -> Result<CallResponse> {
         let context = self.context()?;
@@ -124,26 +404,179 @@ impl AlkaneResponder for MerkleDistributor {
                 let mut pointer = StoragePointer::from_keyword("/initialized");
                 if pointer.get().len() == 0 {
                     pointer.set(Arc::new(vec![0x01]));
-                    if context.incoming_alkanes.0.len() != 1 {
-                        panic!("must send 1 alkane to lock for distribution");
+                    self.add_epoch(
+                        shift_or_err(&mut inputs)?,
+                        shift_bytes32_or_err(&mut inputs)?,
+                        &context.incoming_alkanes.0,
+                    )?;
+                    // both optional: a deadline of 0 never expires, 0 auth units leaves no owner
+                    self.set_deadline(shift_height(&mut inputs)?)?;
+                    let auth_token_units = shift(&mut inputs).unwrap_or(0);
+                    // without an owner nothing could sweep what is left once the deadline passes
+                    if self.deadline() != 0 && auth_token_units == 0 {
+                        return Err(DistributorError::DeadlineWithoutOwner.into());
                     }
-                    self.set_alkane(context.incoming_alkanes.0[0].id.clone());
-                    self.set_length(shift_or_err(&mut inputs)?.try_into().unwrap());
-                    self.set_root(shift_bytes32_or_err(&mut inputs)?);
-                    Ok(CallResponse::default())
+                    // a vesting end height of 0 releases every leaf in full on its first claim
+                    self.set_vesting(shift_height(&mut inputs)?, shift_height(&mut inputs)?)?;
+                    let mut response = CallResponse::default();
+                    if auth_token_units != 0 {
+                        response
+                            .alkanes
+                            .0
+                            .push(self.deploy_auth_token(auth_token_units)?);
+                    }
+                    Ok(response)
                 } else {
-                    Err(anyhow!("already initialized"))
+                    Err(DistributorError::AlreadyInitialized.into())
                 }
             }
             1 => {
                 let mut response = CallResponse::forward(&context.incoming_alkanes);
+                if self.is_expired() {
+                    return Err(DistributorError::DeadlinePassed(self.deadline()).into());
+                }
+                let epoch = self.shift_epoch(&mut inputs)?;
+                let claim = self.verify_output(context.vout, epoch)?;
                 response.alkanes.0.push(AlkaneTransfer {
-                    value: self.verify_output(context.vout)?,
-                    id: self.alkane()?,
+                    value: self.claim(&claim)?,
+                    id: claim.asset,
                 });
                 Ok(response)
             }
-            _ => Err(anyhow!("opcode not recognized")),
+            2 => {
+                self.only_owner()?;
+                if self.deadline() == 0 {
+                    return Err(DistributorError::NoDeadline.into());
+                }
+                if !self.is_expired() {
+                    return Err(DistributorError::DeadlineNotReached(self.deadline()).into());
+                }
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                for asset in self.alkanes()? {
+                    let remaining = self.remaining(&asset);
+                    self.set_remaining(&asset, 0);
+                    response.alkanes.0.push(AlkaneTransfer {
+                        value: remaining,
+                        id: asset,
+                    });
+                }
+                Ok(response)
+            }
+            3 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                if self.is_expired() {
+                    return Err(DistributorError::DeadlinePassed(self.deadline()).into());
+                }
+                let mut payouts: Vec<AlkaneTransfer> = vec![];
+                let epoch = self.shift_epoch(&mut inputs)?;
+                for claim in self.verify_outputs(context.vout, epoch)? {
+                    let value = self.claim(&claim)?;
+                    match payouts.iter_mut().find(|v| v.id == claim.asset) {
+                        Some(payout) => {
+                            payout.value = overflow_error(payout.value.checked_add(value))?
+                        }
+                        None => payouts.push(AlkaneTransfer {
+                            value,
+                            id: claim.asset,
+                        }),
+                    }
+                }
+                response.alkanes.0.extend(payouts);
+                Ok(response)
+            }
+            4 => {
+                self.only_owner()?;
+                if self.is_expired() {
+                    return Err(DistributorError::DeadlinePassed(self.deadline()).into());
+                }
+                let auth_token = self.auth_token()?;
+                let mut response = CallResponse::default();
+                // everything but the auth token funds the new epoch
+                let (returned, deposits): (Vec<AlkaneTransfer>, Vec<AlkaneTransfer>) = context
+                    .incoming_alkanes
+                    .0
+                    .iter()
+                    .cloned()
+                    .partition(|transfer| transfer.id == auth_token);
+                response.alkanes.0.extend(returned);
+                let epoch = self.add_epoch(
+                    shift_or_err(&mut inputs)?,
+                    shift_bytes32_or_err(&mut inputs)?,
+                    &deposits,
+                )?;
+                response.data = epoch.to_le_bytes().to_vec();
+                Ok(response)
+            }
+            100 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                let index: usize = shift_or_err(&mut inputs)?.try_into()?;
+                let epoch = self.shift_epoch(&mut inputs)?;
+                response.data = vec![self.is_claimed(epoch, index) as u8];
+                Ok(response)
+            }
+            101 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.deadline().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            102 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                // the asset is optional and defaults to the first alkane locked
+                let asset = match shift(&mut inputs) {
+                    Some(block) => AlkaneId {
+                        block,
+                        tx: shift_or_err(&mut inputs)?,
+                    },
+                    None => self.alkane()?,
+                };
+                response.data = self.remaining(&asset).to_le_bytes().to_vec();
+                Ok(response)
+            }
+            103 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                let index: usize = shift_or_err(&mut inputs)?.try_into()?;
+                let epoch = self.shift_epoch(&mut inputs)?;
+                response.data = self.claimed_amount(epoch, index).to_le_bytes().to_vec();
+                Ok(response)
+            }
+            104 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.vesting_start().to_le_bytes().to_vec();
+                response.data.extend(&self.vesting_end().to_le_bytes());
+                Ok(response)
+            }
+            105 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.alkanes_pointer().get().as_ref().clone();
+                Ok(response)
+            }
+            106 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.epochs().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            107 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                let epoch = self.shift_epoch(&mut inputs)?;
+                response.data = self.root(epoch)?.to_vec();
+                response.data.extend(&(self.length(epoch) as u64).to_le_bytes());
+                Ok(response)
+            }
+            108 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                let epoch = self.shift_epoch(&mut inputs)?;
+                // the asset is optional and defaults to the first alkane locked
+                let asset = match shift(&mut inputs) {
+                    Some(block) => AlkaneId {
+                        block,
+                        tx: shift_or_err(&mut inputs)?,
+                    },
+                    None => self.alkane()?,
+                };
+                response.data = self.budget(epoch, &asset).to_le_bytes().to_vec();
+                Ok(response)
+            }
+            opcode => Err(DistributorError::UnrecognizedOpcode(opcode).into()),
         }
     }
 }
@@ -5,21 +5,206 @@ use alkanes_runtime::{
     println,
     stdio::{stdout, Write},
 };
-use alkanes_support::utils::shift_or_err;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::witness::find_witness_payload;
+use alkanes_support::utils::{overflow_error, shift, shift_or_err};
 use metashrew_support::compat::{to_passback_ptr};
-use alkanes_support::{parcel::AlkaneTransfer, response::CallResponse};
+use alkanes_support::response::CallResponse;
 use anyhow::{anyhow, Result};
 use metashrew_support::compat::{to_arraybuffer_layout, to_ptr};
+use bitcoin::Transaction;
+use metashrew_support::index_pointer::KeyValuePointer;
+use metashrew_support::utils::consensus_decode;
+use std::sync::Arc;
 
 use alkane_factory_support::factory::MintableToken;
+use alkane_factory_support::multisig::MultisigResponder;
 
 #[derive(Default)]
 pub struct OwnedToken(());
 
-impl MintableToken for OwnedToken {}
+impl MintableToken for OwnedToken {
+    fn increase_total_supply(&self, v: u128) -> Result<()> {
+        let supply = overflow_error(self.total_supply().checked_add(v))?;
+        if self.max_supply() != 0 && supply > self.max_supply() {
+            return Err(anyhow!(
+                "minting {} would exceed the max supply of {}",
+                v,
+                self.max_supply()
+            ));
+        }
+        self.set_total_supply(supply);
+        Ok(())
+    }
+}
 
 impl AuthenticatedResponder for OwnedToken {}
 
+impl MultisigResponder for OwnedToken {}
+
+impl OwnedToken {
+    pub fn max_supply_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/max-supply")
+    }
+    /// The most that can ever be in circulation, 0 if unlimited.
+    pub fn max_supply(&self) -> u128 {
+        self.max_supply_pointer().get_value::<u128>()
+    }
+    pub fn set_max_supply(&self, v: u128) {
+        self.max_supply_pointer().set_value::<u128>(v);
+    }
+    pub fn renounced_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/renounced")
+    }
+    pub fn renounced(&self) -> bool {
+        self.renounced_pointer().get_value::<u8>() != 0
+    }
+    /// Permanently disables owner minting and drops any announced mint. There
+    /// is no opcode to undo this.
+    pub fn renounce(&self) {
+        self.renounced_pointer().set_value::<u8>(0x01);
+        self.pending_amount_pointer().set_value::<u128>(0);
+        self.pending_height_pointer().set_value::<u64>(0);
+    }
+    pub fn check_mintable(&self) -> Result<()> {
+        if self.renounced() {
+            Err(anyhow!("minting has been renounced"))
+        } else {
+            Ok(())
+        }
+    }
+    pub fn frozen_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/metadata-frozen")
+    }
+    pub fn frozen(&self) -> bool {
+        self.frozen_pointer().get_value::<u8>() != 0
+    }
+    /// Makes name, symbol and data immutable. There is no opcode to undo this.
+    pub fn freeze(&self) {
+        self.frozen_pointer().set_value::<u8>(0x01);
+    }
+    pub fn check_not_frozen(&self) -> Result<()> {
+        if self.frozen() {
+            Err(anyhow!("metadata is frozen"))
+        } else {
+            Ok(())
+        }
+    }
+    /// Replaces the data with the witness envelope of this transaction. Unlike
+    /// `set_data` at initialize, a missing envelope is an error rather than
+    /// clearing the data.
+    pub fn update_data(&self) -> Result<()> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let data = find_witness_payload(&tx, 0).ok_or_else(|| {
+            anyhow!("alkanes-factory: witness envelope at index 0 does not contain data")
+        })?;
+        self.data_pointer().set(Arc::new(data));
+        Ok(())
+    }
+    pub fn mint_delay_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/mint-delay")
+    }
+    /// Blocks between announcing a mint and executing it, 0 if owner mints
+    /// are immediate.
+    pub fn mint_delay(&self) -> u64 {
+        self.mint_delay_pointer().get_value::<u64>()
+    }
+    pub fn set_mint_delay(&self, v: u128) -> Result<()> {
+        self.mint_delay_pointer().set_value::<u64>(v.try_into()?);
+        Ok(())
+    }
+    pub fn pending_amount_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/pending-mint/amount")
+    }
+    pub fn pending_amount(&self) -> u128 {
+        self.pending_amount_pointer().get_value::<u128>()
+    }
+    pub fn pending_height_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/pending-mint/height")
+    }
+    /// The earliest height the pending mint can execute at.
+    pub fn pending_height(&self) -> u64 {
+        self.pending_height_pointer().get_value::<u64>()
+    }
+    pub fn check_not_timelocked(&self) -> Result<()> {
+        if self.mint_delay() != 0 {
+            Err(anyhow!("minting is timelocked, announce the mint first"))
+        } else {
+            Ok(())
+        }
+    }
+    /// Records a mint of `amount` that can execute `mint_delay` blocks from
+    /// now. Only one mint can be pending at a time.
+    pub fn announce_mint(&self, amount: u128) -> Result<()> {
+        if self.mint_delay() == 0 {
+            return Err(anyhow!("minting is not timelocked"));
+        }
+        if amount == 0 {
+            return Err(anyhow!("announced mint must be greater than 0"));
+        }
+        let supply = overflow_error(self.total_supply().checked_add(amount))?;
+        if self.max_supply() != 0 && supply > self.max_supply() {
+            return Err(anyhow!(
+                "announced mint of {} would exceed the max supply of {}",
+                amount,
+                self.max_supply()
+            ));
+        }
+        if self.pending_amount() != 0 {
+            return Err(anyhow!(
+                "a mint of {} is already pending until height {}",
+                self.pending_amount(),
+                self.pending_height()
+            ));
+        }
+        let height = self
+            .height()
+            .checked_add(self.mint_delay())
+            .ok_or_else(|| anyhow!("overflow error"))?;
+        self.pending_amount_pointer().set_value::<u128>(amount);
+        self.pending_height_pointer().set_value::<u64>(height);
+        Ok(())
+    }
+    /// Clears the pending mint and returns its amount.
+    pub fn take_pending_mint(&self) -> Result<u128> {
+        let amount = self.pending_amount();
+        if amount == 0 {
+            return Err(anyhow!("no mint is pending"));
+        }
+        self.pending_amount_pointer().set_value::<u128>(0);
+        self.pending_height_pointer().set_value::<u64>(0);
+        Ok(amount)
+    }
+    /// Gates privileged opcodes on the signer set when one is configured and
+    /// on the auth token otherwise.
+    pub fn authorize(&self) -> Result<()> {
+        if self.multisig_enabled() {
+            self.only_signers()
+        } else {
+            self.only_owner()
+        }
+    }
+    /// The key `AuthenticatedResponder` reads the auth token id from.
+    pub fn auth_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/auth")
+    }
+    /// Points ownership at a different auth alkane and drops any signer set.
+    /// The old auth token or signers no longer pass `authorize` once this
+    /// returns.
+    pub fn transfer_ownership(&self, myself: &AlkaneId, v: AlkaneId) -> Result<()> {
+        if &v == myself {
+            return Err(anyhow!("token cannot own itself"));
+        }
+        if !self.multisig_enabled() && v == self.auth_token()? {
+            return Err(anyhow!("{}:{} already owns this token", v.block, v.tx));
+        }
+        self.auth_pointer().set(Arc::new(v.into()));
+        self.clear_signers();
+        Ok(())
+    }
+}
+
 impl AlkaneResponder for OwnedToken {
     fn execute(&self) -> Result<CallResponse> {
         let context = self.context()?;
@@ -33,23 +218,99 @@ impl AlkaneResponder for OwnedToken {
                 let auth_token_units = shift_or_err(&mut inputs)?;
                 let token_units = shift_or_err(&mut inputs)?;
                 self.set_name_and_symbol(shift_or_err(&mut inputs)?, shift_or_err(&mut inputs)?);
+                // optional max supply, 0 is unlimited
+                self.set_max_supply(shift(&mut inputs).unwrap_or(0));
+                // optional k-of-n signer set as <threshold, n, block_1, tx_1, ..., block_n, tx_n>,
+                // a threshold of 0 leaves the auth token in control
+                let threshold = shift(&mut inputs).unwrap_or(0);
+                if threshold != 0 {
+                    let count = shift_or_err(&mut inputs)?;
+                    let mut signers: Vec<AlkaneId> = vec![];
+                    for _ in 0..count {
+                        signers.push(AlkaneId {
+                            block: shift_or_err(&mut inputs)?,
+                            tx: shift_or_err(&mut inputs)?,
+                        });
+                    }
+                    self.set_signers(signers, threshold)?;
+                }
+                // optional mint delay in blocks, owner mints then have to be announced first
+                self.set_mint_delay(shift(&mut inputs).unwrap_or(0))?;
                 response
                     .alkanes
                     .0
                     .push(self.deploy_auth_token(auth_token_units)?);
-                response.alkanes.0.push(AlkaneTransfer {
-                    id: context.myself.clone(),
-                    value: token_units,
-                });
+                response.alkanes.0.push(self.mint(&context, token_units)?);
                 Ok(response)
             }
             77 => {
-                self.only_owner()?;
+                self.authorize()?;
+                self.check_mintable()?;
+                self.check_not_timelocked()?;
                 let token_units = shift_or_err(&mut inputs)?;
                 let transfer = self.mint(&context, token_units)?;
                 response.alkanes.0.push(transfer);
                 Ok(response)
             }
+            78 => {
+                self.authorize()?;
+                self.check_mintable()?;
+                self.renounce();
+                Ok(response)
+            }
+            79 => {
+                self.authorize()?;
+                self.transfer_ownership(
+                    &context.myself,
+                    AlkaneId {
+                        block: shift_or_err(&mut inputs)?,
+                        tx: shift_or_err(&mut inputs)?,
+                    },
+                )?;
+                Ok(response)
+            }
+            80 => {
+                self.authorize()?;
+                self.check_not_frozen()?;
+                self.update_data()?;
+                Ok(response)
+            }
+            81 => {
+                self.authorize()?;
+                self.check_not_frozen()?;
+                self.try_set_name_and_symbol(shift_or_err(&mut inputs)?, shift_or_err(&mut inputs)?)?;
+                Ok(response)
+            }
+            82 => {
+                self.authorize()?;
+                self.check_not_frozen()?;
+                self.freeze();
+                Ok(response)
+            }
+            83 => {
+                self.authorize()?;
+                self.check_mintable()?;
+                self.announce_mint(shift_or_err(&mut inputs)?)?;
+                Ok(response)
+            }
+            84 => {
+                self.authorize()?;
+                self.check_mintable()?;
+                if self.pending_amount() != 0 && self.height() < self.pending_height() {
+                    return Err(anyhow!(
+                        "announced mint cannot execute before height {}",
+                        self.pending_height()
+                    ));
+                }
+                let token_units = self.take_pending_mint()?;
+                response.alkanes.0.push(self.mint(&context, token_units)?);
+                Ok(response)
+            }
+            85 => {
+                self.authorize()?;
+                self.take_pending_mint()?;
+                Ok(response)
+            }
             99 => {
                 response.data = self.name().into_bytes().to_vec();
                 Ok(response)
@@ -62,6 +323,35 @@ impl AlkaneResponder for OwnedToken {
                 response.data = self.total_supply().to_le_bytes().to_vec();
                 Ok(response)
             }
+            102 => {
+                let auth_token = self.auth_token()?;
+                response.data = auth_token.block.to_le_bytes().to_vec();
+                response.data.extend(&auth_token.tx.to_le_bytes());
+                response.data.push(self.renounced() as u8);
+                // a nonzero threshold means signers control the token and the auth token
+                // alone no longer passes, see 105 for the signer set
+                response.data.extend(&self.threshold().to_le_bytes());
+                Ok(response)
+            }
+            103 => {
+                response.data = self.max_supply().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            104 => {
+                response.data = vec![self.frozen() as u8];
+                Ok(response)
+            }
+            105 => {
+                response.data = self.threshold().to_le_bytes().to_vec();
+                response.data.extend(self.signers_pointer().get().as_ref());
+                Ok(response)
+            }
+            106 => {
+                response.data = self.pending_amount().to_le_bytes().to_vec();
+                response.data.extend(&self.pending_height().to_le_bytes());
+                response.data.extend(&self.mint_delay().to_le_bytes());
+                Ok(response)
+            }
             1000 => {
                 response.data = self.data();
                 Ok(response)
@@ -0,0 +1,153 @@
+use alkane_factory_support::protostone::CallMessage;
+use alkane_factory_support::voucher::Voucher;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_runtime::{declare_alkane, runtime::AlkaneResponder};
+use alkanes_support::{
+    id::AlkaneId,
+    parcel::AlkaneTransfer,
+    response::CallResponse,
+    utils::{overflow_error, shift_bytes32_or_err, shift_or_err},
+    witness::find_witness_payload,
+};
+use anyhow::{anyhow, Result};
+use bitcoin::secp256k1::{schnorr::Signature, Message, Secp256k1, XOnlyPublicKey};
+use bitcoin::Transaction;
+use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
+use metashrew_support::index_pointer::KeyValuePointer;
+use protorune_support::utils::consensus_decode;
+use std::io::Cursor;
+use std::sync::Arc;
+
+#[derive(Default)]
+struct VoucherDistributor(());
+
+impl VoucherDistributor {
+    pub fn verify_output(&self, vout: u32) -> Result<Voucher> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let message = CallMessage::decode(tx, vout)?;
+        message.check_no_edicts()?;
+        let output = message.pointer_output()?;
+        let mut cursor: Cursor<Vec<u8>> =
+            Cursor::<Vec<u8>>::new(find_witness_payload(&message.tx, 0).ok_or_else(|| {
+                anyhow!("alkanes-factory: witness envelope at index 0 does not contain data")
+            })?);
+        let voucher = Voucher::parse(&mut cursor)?;
+        let digest = voucher.digest(&self.context()?.myself)?;
+        Secp256k1::verification_only()
+            .verify_schnorr(
+                &Signature::from_slice(&voucher.signature)?,
+                &Message::from_digest(digest),
+                &self.signer()?,
+            )
+            .map_err(|_| anyhow!("voucher signature verification failure"))?;
+        if output.script_pubkey.as_bytes() != voucher.script_pubkey.as_slice() {
+            Err(anyhow!("spendable output created does not match voucher"))
+        } else {
+            Ok(voucher)
+        }
+    }
+    pub fn signer_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/signer")
+    }
+    pub fn signer(&self) -> Result<XOnlyPublicKey> {
+        Ok(XOnlyPublicKey::from_slice(self.signer_pointer().get().as_ref())?)
+    }
+    pub fn set_signer(&self, v: Vec<u8>) -> Result<()> {
+        XOnlyPublicKey::from_slice(&v)
+            .map_err(|_| anyhow!("signer is not a valid x-only public key"))?;
+        self.signer_pointer().set(Arc::new(v));
+        Ok(())
+    }
+    pub fn nonce_pointer(&self, nonce: u128) -> StoragePointer {
+        StoragePointer::from_keyword("/nonce/").select(&nonce.to_le_bytes().to_vec())
+    }
+    pub fn is_nonce_used(&self, nonce: u128) -> bool {
+        self.nonce_pointer(nonce).get_value::<u8>() != 0
+    }
+    pub fn use_nonce(&self, nonce: u128) {
+        self.nonce_pointer(nonce).set_value::<u8>(0x01);
+    }
+    pub fn alkane_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/alkane")
+    }
+    pub fn alkane(&self) -> Result<AlkaneId> {
+        Ok(self.alkane_pointer().get().as_ref().clone().try_into()?)
+    }
+    pub fn set_alkane(&self, v: AlkaneId) {
+        self.alkane_pointer().set(Arc::<Vec<u8>>::new(v.into()));
+    }
+    pub fn remaining_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/remaining")
+    }
+    pub fn remaining(&self) -> u128 {
+        self.remaining_pointer().get_value::<u128>()
+    }
+    pub fn set_remaining(&self, v: u128) {
+        self.remaining_pointer().set_value::<u128>(v);
+    }
+    pub fn decrease_remaining(&self, v: u128) -> Result<()> {
+        self.set_remaining(overflow_error(self.remaining().checked_sub(v))?);
+        Ok(())
+    }
+}
+
+impl AlkaneResponder for VoucherDistributor {
+    fn execute(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut inputs = context.inputs.clone();
+        match shift_or_err(&mut inputs)? {
+            0 => {
+                let mut pointer = StoragePointer::from_keyword("/initialized");
+                if pointer.get().len() == 0 {
+                    pointer.set(Arc::new(vec![0x01]));
+                    if context.incoming_alkanes.0.len() != 1 {
+                        return Err(anyhow!("must send 1 alkane to lock for distribution"));
+                    }
+                    self.set_alkane(context.incoming_alkanes.0[0].id.clone());
+                    self.set_remaining(context.incoming_alkanes.0[0].value);
+                    // the signer's 32 byte x-only key as two inputs: the key bytes reversed,
+                    // then split into two little-endian u128 values
+                    self.set_signer(shift_bytes32_or_err(&mut inputs)?)?;
+                    Ok(CallResponse::default())
+                } else {
+                    Err(anyhow!("already initialized"))
+                }
+            }
+            1 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                let voucher = self.verify_output(context.vout)?;
+                if voucher.expiry != 0 && self.height() >= voucher.expiry {
+                    return Err(anyhow!("voucher has expired"));
+                }
+                if self.is_nonce_used(voucher.nonce) {
+                    return Err(anyhow!("voucher nonce {} has already been used", voucher.nonce));
+                }
+                self.use_nonce(voucher.nonce);
+                self.decrease_remaining(voucher.amount)?;
+                response.alkanes.0.push(AlkaneTransfer {
+                    value: voucher.amount,
+                    id: self.alkane()?,
+                });
+                Ok(response)
+            }
+            100 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = vec![self.is_nonce_used(shift_or_err(&mut inputs)?) as u8];
+                Ok(response)
+            }
+            101 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.signer_pointer().get().as_ref().clone();
+                Ok(response)
+            }
+            102 => {
+                let mut response = CallResponse::forward(&context.incoming_alkanes);
+                response.data = self.remaining().to_le_bytes().to_vec();
+                Ok(response)
+            }
+            _ => Err(anyhow!("opcode not recognized")),
+        }
+    }
+}
+
+declare_alkane!{ VoucherDistributor }
@@ -0,0 +1,116 @@
+use alkane_factory_support::merkle::MerkleLeaf;
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use rs_merkle::{algorithms::Sha256, MerkleTree};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Allocation {
+    pub script_pubkey: Vec<u8>,
+    pub amount: u128,
+    /// Leave unset to pay out the first alkane locked in the distributor.
+    pub asset: Option<AlkaneId>,
+}
+
+/// Everything needed to deploy and claim from a merkle-distributor: `root` and
+/// `length` go to the initialize cellpack, `payloads[i]` is the witness
+/// envelope content for the claim of `leaves[i]`.
+#[derive(Clone, Debug, Default)]
+pub struct Distribution {
+    pub root: [u8; 32],
+    pub length: usize,
+    pub leaves: Vec<MerkleLeaf>,
+    pub payloads: Vec<Vec<u8>>,
+}
+
+impl Distribution {
+    /// The payload for the only allocation to `script_pubkey`. Errors when the
+    /// scriptPubKey has no allocation or several, see `payloads_for`.
+    pub fn payload_for(&self, script_pubkey: &[u8]) -> Result<&Vec<u8>> {
+        match self.payloads_for(script_pubkey).as_slice() {
+            [payload] => Ok(payload),
+            [] => Err(anyhow!("no allocation pays this scriptPubKey")),
+            payloads => Err(anyhow!(
+                "{} allocations pay this scriptPubKey, pick one from payloads_for",
+                payloads.len()
+            )),
+        }
+    }
+    /// The payloads for every allocation to `script_pubkey`, in leaf order.
+    pub fn payloads_for(&self, script_pubkey: &[u8]) -> Vec<&Vec<u8>> {
+        self.leaves
+            .iter()
+            .zip(self.payloads.iter())
+            .filter(|(leaf, _)| leaf.script_pubkey.as_slice() == script_pubkey)
+            .map(|(_, payload)| payload)
+            .collect()
+    }
+    /// Witness envelope content for a batch claim of the leaves at `indices`.
+    pub fn multi_payload(&self, indices: &[usize]) -> Result<Vec<u8>> {
+        let mut indices = indices.to_vec();
+        indices.sort();
+        indices.dedup();
+        let count: u32 = indices
+            .len()
+            .try_into()
+            .map_err(|_| anyhow!("too many leaves for a batch claim"))?;
+        let hashes = self
+            .leaves
+            .iter()
+            .map(|leaf| leaf.hash())
+            .collect::<Result<Vec<[u8; 32]>>>()?;
+        let tree = MerkleTree::<Sha256>::from_leaves(&hashes);
+        let mut payload: Vec<u8> = count.to_le_bytes().to_vec();
+        for i in indices.iter() {
+            payload.extend(
+                self.leaves
+                    .get(*i)
+                    .ok_or_else(|| anyhow!("no leaf at index {}", i))?
+                    .encode()?,
+            );
+        }
+        payload.extend(tree.proof(&indices).to_bytes());
+        Ok(payload)
+    }
+}
+
+pub fn build(allocations: &[Allocation]) -> Result<Distribution> {
+    if allocations.is_empty() {
+        return Err(anyhow!("cannot build a distribution with no allocations"));
+    }
+    let leaves = allocations
+        .iter()
+        .enumerate()
+        .map(|(i, allocation)| -> Result<MerkleLeaf> {
+            Ok(MerkleLeaf {
+                index: i
+                    .try_into()
+                    .map_err(|_| anyhow!("too many allocations for a u32 leaf index"))?,
+                amount: allocation.amount,
+                asset: allocation.asset.clone(),
+                script_pubkey: allocation.script_pubkey.clone(),
+            })
+        })
+        .collect::<Result<Vec<MerkleLeaf>>>()?;
+    let hashes = leaves
+        .iter()
+        .map(|leaf| leaf.hash())
+        .collect::<Result<Vec<[u8; 32]>>>()?;
+    let tree = MerkleTree::<Sha256>::from_leaves(&hashes);
+    let root = tree
+        .root()
+        .ok_or_else(|| anyhow!("merkle tree has no root"))?;
+    let payloads = leaves
+        .iter()
+        .map(|leaf| -> Result<Vec<u8>> {
+            let mut payload = leaf.encode()?;
+            payload.extend(tree.proof(&[leaf.index as usize]).to_bytes());
+            Ok(payload)
+        })
+        .collect::<Result<Vec<Vec<u8>>>>()?;
+    Ok(Distribution {
+        root,
+        length: leaves.len(),
+        leaves,
+        payloads,
+    })
+}
@@ -1,2 +1,5 @@
 pub const ALKANE_FACTORY_OWNED_TOKEN_ID: u128 =  0x0fff;
 pub const ALKANE_FACTORY_FREE_MINT_ID: u128 = 0x0ffe;
+pub const ALKANE_FACTORY_MERKLE_DISTRIBUTOR_ID: u128 = 0x0ffd;
+pub const ALKANE_FACTORY_VOUCHER_DISTRIBUTOR_ID: u128 = 0x0ffc;
+pub const ALKANE_FACTORY_BONDING_CURVE_ID: u128 = 0x0ffb;
@@ -0,0 +1,148 @@
+use crate::factory::MintableToken;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::context::Context;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::utils::overflow_error;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// The price rises by `factor` for every `PRICE_SCALE` tokens in supply.
+pub const CURVE_LINEAR: u128 = 0;
+/// The price doubles every `factor` tokens in supply.
+pub const CURVE_EXPONENTIAL: u128 = 1;
+
+/// Prices are quoted in base alkane units per `PRICE_SCALE` token units, so
+/// tokens with 8 decimals are priced per whole token.
+pub const PRICE_SCALE: u128 = 100_000_000;
+
+/// A token sold along a price curve over its own supply, in exchange for a
+/// base alkane held in reserve. Buys round up and sells round down, so the
+/// reserve always covers selling back the whole supply.
+pub trait BondingCurve: MintableToken {
+    fn curve_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/curve/kind")
+    }
+    fn curve(&self) -> u128 {
+        self.curve_pointer().get_value::<u128>()
+    }
+    fn base_price_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/curve/base-price")
+    }
+    fn base_price(&self) -> u128 {
+        self.base_price_pointer().get_value::<u128>()
+    }
+    fn factor_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/curve/factor")
+    }
+    fn factor(&self) -> u128 {
+        self.factor_pointer().get_value::<u128>()
+    }
+    fn set_curve(&self, curve: u128, base_price: u128, factor: u128) -> Result<()> {
+        match curve {
+            CURVE_LINEAR => {
+                if base_price == 0 && factor == 0 {
+                    return Err(anyhow!("linear curve needs a base price or a slope"));
+                }
+            }
+            CURVE_EXPONENTIAL => {
+                if base_price == 0 {
+                    return Err(anyhow!("exponential curve needs a base price"));
+                }
+                if factor == 0 {
+                    return Err(anyhow!("doubling interval must be greater than 0"));
+                }
+            }
+            _ => return Err(anyhow!("unrecognized curve: {}", curve)),
+        }
+        self.curve_pointer().set_value::<u128>(curve);
+        self.base_price_pointer().set_value::<u128>(base_price);
+        self.factor_pointer().set_value::<u128>(factor);
+        Ok(())
+    }
+    fn base_alkane_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/base-alkane")
+    }
+    fn base_alkane(&self) -> Result<AlkaneId> {
+        Ok(self.base_alkane_pointer().get().as_ref().clone().try_into()?)
+    }
+    fn set_base_alkane(&self, context: &Context, v: AlkaneId) -> Result<()> {
+        if v == AlkaneId::default() {
+            return Err(anyhow!("a curve needs the alkane it is priced in"));
+        }
+        if v == context.myself {
+            return Err(anyhow!("a token cannot be priced in itself"));
+        }
+        self.base_alkane_pointer().set(Arc::new(v.into()));
+        Ok(())
+    }
+    fn reserve_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/reserve")
+    }
+    fn reserve(&self) -> u128 {
+        self.reserve_pointer().get_value::<u128>()
+    }
+    fn set_reserve(&self, v: u128) {
+        self.reserve_pointer().set_value::<u128>(v);
+    }
+    /// The price of the `count` tokens following `supply`, as a numerator and
+    /// denominator so that buys and sells can round in the reserve's favour.
+    fn integral(&self, supply: u128, count: u128) -> Result<(u128, u128)> {
+        let (base_price, factor) = (self.base_price(), self.factor());
+        match self.curve() {
+            CURVE_EXPONENTIAL => {
+                let mut at = supply;
+                let mut left = count;
+                let mut total = 0u128;
+                while left != 0 {
+                    let doublings = at / factor;
+                    if doublings >= 128 {
+                        return Err(anyhow!("overflow error"));
+                    }
+                    let in_step = left.min(factor - at % factor);
+                    let price = overflow_error(base_price.checked_mul(1u128 << doublings))?;
+                    total = overflow_error(total.checked_add(overflow_error(price.checked_mul(in_step))?))?;
+                    at = overflow_error(at.checked_add(in_step))?;
+                    left -= in_step;
+                }
+                Ok((total, PRICE_SCALE))
+            }
+            _ => {
+                // sum of supply + i for i in 0..count, halving whichever of
+                // count and count - 1 is even so the product stays exact
+                let steps = if count == 0 {
+                    0
+                } else if count % 2 == 0 {
+                    overflow_error((count / 2).checked_mul(count - 1))?
+                } else {
+                    overflow_error(count.checked_mul((count - 1) / 2))?
+                };
+                let offsets = overflow_error(
+                    overflow_error(supply.checked_mul(count))?.checked_add(steps),
+                )?;
+                let flat = overflow_error(
+                    overflow_error(base_price.checked_mul(PRICE_SCALE))?.checked_mul(count),
+                )?;
+                let total = overflow_error(
+                    flat.checked_add(overflow_error(factor.checked_mul(offsets))?),
+                )?;
+                Ok((total, PRICE_SCALE * PRICE_SCALE))
+            }
+        }
+    }
+    /// What buying `count` tokens costs at the current supply, rounded up.
+    fn buy_cost(&self, count: u128) -> Result<u128> {
+        let (numerator, denominator) = self.integral(self.total_supply(), count)?;
+        Ok(numerator / denominator + (numerator % denominator != 0) as u128)
+    }
+    /// What selling `count` tokens back refunds at the current supply,
+    /// rounded down.
+    fn sell_refund(&self, count: u128) -> Result<u128> {
+        let supply = self
+            .total_supply()
+            .checked_sub(count)
+            .ok_or_else(|| anyhow!("cannot sell more than the {} tokens in supply", self.total_supply()))?;
+        let (numerator, denominator) = self.integral(supply, count)?;
+        Ok(numerator / denominator)
+    }
+}
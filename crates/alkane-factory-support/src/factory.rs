@@ -25,6 +25,10 @@ fn symbol_pointer() -> StoragePointer {
 }
 
 pub fn trim(v: u128) -> String {
+    try_trim(v).unwrap()
+}
+
+pub fn try_trim(v: u128) -> Result<String> {
     String::from_utf8(
         v.to_le_bytes()
             .into_iter()
@@ -35,7 +39,7 @@ pub fn trim(v: u128) -> String {
                 r
             }),
     )
-    .unwrap()
+    .map_err(|_| anyhow!("{:#x} does not encode a utf-8 string", v))
 }
 
 pub struct ContextHandle(());
@@ -60,6 +64,20 @@ pub trait MintableToken {
         self.set_string_field(self.name_pointer(), name);
         self.set_string_field(self.symbol_pointer(), symbol);
     }
+    /// Rejects a name or symbol that is not utf-8 or is empty once trimmed.
+    fn validate_name_and_symbol(&self, name: u128, symbol: u128) -> Result<()> {
+        for (field, v) in [("name", name), ("symbol", symbol)] {
+            if try_trim(v).map_err(|e| anyhow!("invalid {}: {}", field, e))?.is_empty() {
+                return Err(anyhow!("{} must not be empty", field));
+            }
+        }
+        Ok(())
+    }
+    fn try_set_name_and_symbol(&self, name: u128, symbol: u128) -> Result<()> {
+        self.validate_name_and_symbol(name, symbol)?;
+        self.set_name_and_symbol(name, symbol);
+        Ok(())
+    }
     fn name_pointer(&self) -> StoragePointer {
         name_pointer()
     }
@@ -1,2 +1,8 @@
 pub mod constants;
+pub mod curve;
 pub mod factory;
+pub mod merkle;
+pub mod multisig;
+pub mod payment;
+pub mod protostone;
+pub mod voucher;
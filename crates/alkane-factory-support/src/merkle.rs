@@ -0,0 +1,122 @@
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use metashrew_support::utils::{consume_exact, consume_sized_int, consume_to_end};
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleProof};
+use std::io::Cursor;
+
+pub const LEAF_VERSION_SCRIPT_PUBKEY: u8 = 0x01;
+pub const LEAF_VERSION_ASSET: u8 = 0x02;
+
+/// A merkle distributor allocation, encoded as
+/// `version:u8 | index:u32 | amount:u128 | [block:u128 | tx:u128] | script_len:u16 | script_pubkey`
+/// with all integers little-endian. The asset id is only present in version 2
+/// leaves; version 1 leaves pay out the first alkane locked in the distributor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleLeaf {
+    pub index: u32,
+    pub amount: u128,
+    pub asset: Option<AlkaneId>,
+    pub script_pubkey: Vec<u8>,
+}
+
+impl MerkleLeaf {
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let script_len: u16 = self
+            .script_pubkey
+            .len()
+            .try_into()
+            .map_err(|_| anyhow!("script_pubkey is too long for a merkle leaf"))?;
+        let mut result: Vec<u8> = vec![match self.asset {
+            Some(_) => LEAF_VERSION_ASSET,
+            None => LEAF_VERSION_SCRIPT_PUBKEY,
+        }];
+        result.extend(&self.index.to_le_bytes());
+        result.extend(&self.amount.to_le_bytes());
+        if let Some(asset) = self.asset.as_ref() {
+            result.extend(&asset.block.to_le_bytes());
+            result.extend(&asset.tx.to_le_bytes());
+        }
+        result.extend(&script_len.to_le_bytes());
+        result.extend(&self.script_pubkey);
+        Ok(result)
+    }
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        Ok(Sha256::hash(&self.encode()?))
+    }
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<MerkleLeaf> {
+        let version = consume_sized_int::<u8>(cursor)?;
+        if version != LEAF_VERSION_SCRIPT_PUBKEY && version != LEAF_VERSION_ASSET {
+            return Err(anyhow!("unsupported merkle leaf version: {}", version));
+        }
+        let index = consume_sized_int::<u32>(cursor)?;
+        let amount = consume_sized_int::<u128>(cursor)?;
+        let asset = if version == LEAF_VERSION_ASSET {
+            Some(AlkaneId {
+                block: consume_sized_int::<u128>(cursor)?,
+                tx: consume_sized_int::<u128>(cursor)?,
+            })
+        } else {
+            None
+        };
+        let script_len = consume_sized_int::<u16>(cursor)?;
+        let script_pubkey = consume_exact(cursor, script_len as usize)?;
+        Ok(MerkleLeaf {
+            index,
+            amount,
+            asset,
+            script_pubkey,
+        })
+    }
+}
+
+/// Checks a claim payload of the form `leaf | proof` against a distribution
+/// root, returning the leaf it proves.
+pub fn verify_payload(payload: Vec<u8>, root: [u8; 32], length: usize) -> Result<MerkleLeaf> {
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(payload);
+    let leaf = MerkleLeaf::parse(&mut cursor)?;
+    let proof = consume_to_end(&mut cursor)?;
+    if MerkleProof::<Sha256>::try_from(proof)?.verify(
+        root,
+        &[leaf.index as usize],
+        &[leaf.hash()?],
+        length,
+    ) {
+        Ok(leaf)
+    } else {
+        Err(anyhow!("proof verification failure"))
+    }
+}
+
+/// Checks a batch claim payload of the form `count:u32 | leaf * count | proof`,
+/// where `proof` is a multiproof over the leaves listed in ascending index order.
+pub fn verify_multi_payload(
+    payload: Vec<u8>,
+    root: [u8; 32],
+    length: usize,
+) -> Result<Vec<MerkleLeaf>> {
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(payload);
+    let count = consume_sized_int::<u32>(&mut cursor)?;
+    if count == 0 {
+        return Err(anyhow!("batch claim must contain at least one leaf"));
+    }
+    let leaves = (0..count)
+        .map(|_| MerkleLeaf::parse(&mut cursor))
+        .collect::<Result<Vec<MerkleLeaf>>>()?;
+    if leaves.windows(2).any(|pair| pair[0].index >= pair[1].index) {
+        return Err(anyhow!("batch claim leaves must be in strictly ascending index order"));
+    }
+    let proof = consume_to_end(&mut cursor)?;
+    let indices = leaves
+        .iter()
+        .map(|leaf| leaf.index as usize)
+        .collect::<Vec<usize>>();
+    let hashes = leaves
+        .iter()
+        .map(|leaf| leaf.hash())
+        .collect::<Result<Vec<[u8; 32]>>>()?;
+    if MerkleProof::<Sha256>::try_from(proof)?.verify(root, &indices, &hashes, length) {
+        Ok(leaves)
+    } else {
+        Err(anyhow!("proof verification failure"))
+    }
+}
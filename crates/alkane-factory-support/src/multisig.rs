@@ -0,0 +1,76 @@
+use alkanes_runtime::{runtime::AlkaneResponder, storage::StoragePointer};
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// k-of-n authorization over a set of signer alkanes, used alongside
+/// `AuthenticatedResponder`. A call is authorized when at least `threshold`
+/// distinct signers are present in its incoming alkanes. A threshold of 0
+/// means no signer set is configured.
+pub trait MultisigResponder: AlkaneResponder {
+    fn signers_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/multisig/signers")
+    }
+    fn threshold_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/multisig/threshold")
+    }
+    fn threshold(&self) -> u128 {
+        self.threshold_pointer().get_value::<u128>()
+    }
+    fn multisig_enabled(&self) -> bool {
+        self.threshold() != 0
+    }
+    fn signers(&self) -> Result<Vec<AlkaneId>> {
+        self.signers_pointer()
+            .get()
+            .chunks(32)
+            .map(|v| -> Result<AlkaneId> { Ok(v.to_vec().try_into()?) })
+            .collect()
+    }
+    fn set_signers(&self, signers: Vec<AlkaneId>, threshold: u128) -> Result<()> {
+        if threshold == 0 || threshold > signers.len() as u128 {
+            return Err(anyhow!(
+                "threshold must be between 1 and the {} signers",
+                signers.len()
+            ));
+        }
+        let mut encoded: Vec<u8> = vec![];
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[..i].contains(signer) {
+                return Err(anyhow!("signer {}:{} is listed twice", signer.block, signer.tx));
+            }
+            encoded.extend(<AlkaneId as Into<Vec<u8>>>::into(signer.clone()));
+        }
+        self.signers_pointer().set(Arc::new(encoded));
+        self.threshold_pointer().set_value::<u128>(threshold);
+        Ok(())
+    }
+    fn clear_signers(&self) {
+        self.signers_pointer().set(Arc::new(vec![]));
+        self.threshold_pointer().set_value::<u128>(0);
+    }
+    fn only_signers(&self) -> Result<()> {
+        let context = self.context()?;
+        let signers = self.signers()?;
+        let present = signers
+            .iter()
+            .filter(|signer| {
+                context
+                    .incoming_alkanes
+                    .0
+                    .iter()
+                    .any(|transfer| &transfer.id == *signer && transfer.value != 0)
+            })
+            .count() as u128;
+        if present < self.threshold() {
+            Err(anyhow!(
+                "{} of {} required signers present",
+                present,
+                self.threshold()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use alkanes_support::context::Context;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::AlkaneTransfer;
+use alkanes_support::response::CallResponse;
+use alkanes_support::utils::overflow_error;
+use anyhow::{anyhow, Result};
+
+/// Takes `cost` of `alkane` out of the incoming alkanes. The returned response
+/// refunds any overpayment and every unrelated alkane.
+pub fn take_payment(context: &Context, alkane: &AlkaneId, cost: u128) -> Result<CallResponse> {
+    let mut response = CallResponse::default();
+    let mut paid = 0u128;
+    for transfer in context.incoming_alkanes.0.iter() {
+        if &transfer.id == alkane {
+            paid = overflow_error(paid.checked_add(transfer.value))?;
+        } else {
+            response.alkanes.0.push(transfer.clone());
+        }
+    }
+    if paid < cost {
+        return Err(anyhow!(
+            "costs {} of {}:{} but only {} was sent",
+            cost,
+            alkane.block,
+            alkane.tx,
+            paid
+        ));
+    }
+    if paid > cost {
+        response.alkanes.0.push(AlkaneTransfer {
+            id: alkane.clone(),
+            value: paid - cost,
+        });
+    }
+    Ok(response)
+}
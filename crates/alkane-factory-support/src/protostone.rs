@@ -0,0 +1,84 @@
+use bitcoin::{Transaction, TxOut};
+use ordinals::{Artifact, Runestone};
+use protorune_support::protostone::Protostone;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MessageError {
+    RunestoneNotFound,
+    InvalidProtostones,
+    ProtostoneNotFound(u32),
+    MessageHasEdicts,
+    MissingPointer,
+    PointerIsProtomessage(u32),
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RunestoneNotFound => write!(f, "runestone decipher failed"),
+            Self::InvalidProtostones => write!(f, "runestone does not carry valid protostones"),
+            Self::ProtostoneNotFound(vout) => {
+                write!(f, "vout {} does not reference a protostone", vout)
+            }
+            Self::MessageHasEdicts => write!(f, "message cannot contain edicts, only a pointer"),
+            Self::MissingPointer => write!(f, "no pointer in message"),
+            Self::PointerIsProtomessage(pointer) => {
+                write!(f, "pointer {} cannot be a protomessage", pointer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+/// The transaction executing a call, decoded together with the protostone at
+/// the call's vout.
+#[derive(Clone, Debug)]
+pub struct CallMessage {
+    pub tx: Transaction,
+    pub protostones: Vec<Protostone>,
+    /// Index of the calling protostone in `protostones`.
+    pub position: usize,
+}
+
+impl CallMessage {
+    pub fn decode(tx: Transaction, vout: u32) -> Result<CallMessage, MessageError> {
+        let runestone = match Runestone::decipher(&tx) {
+            Some(Artifact::Runestone(runestone)) => runestone,
+            _ => return Err(MessageError::RunestoneNotFound),
+        };
+        let protostones =
+            Protostone::from_runestone(&runestone).map_err(|_| MessageError::InvalidProtostones)?;
+        let position = (vout as usize)
+            .checked_sub(tx.output.len() + 1)
+            .filter(|position| *position < protostones.len())
+            .ok_or(MessageError::ProtostoneNotFound(vout))?;
+        Ok(CallMessage {
+            tx,
+            protostones,
+            position,
+        })
+    }
+    pub fn protostone(&self) -> &Protostone {
+        &self.protostones[self.position]
+    }
+    pub fn check_no_edicts(&self) -> Result<(), MessageError> {
+        if self.protostone().edicts.len() != 0 {
+            Err(MessageError::MessageHasEdicts)
+        } else {
+            Ok(())
+        }
+    }
+    pub fn pointer(&self) -> Result<u32, MessageError> {
+        self.protostone().pointer.ok_or(MessageError::MissingPointer)
+    }
+    /// The spendable output the calling protostone points at.
+    pub fn pointer_output(&self) -> Result<&TxOut, MessageError> {
+        let pointer = self.pointer()?;
+        self.tx
+            .output
+            .get(pointer as usize)
+            .ok_or(MessageError::PointerIsProtomessage(pointer))
+    }
+}
@@ -0,0 +1,64 @@
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::{sha256, Hash};
+use metashrew_support::utils::{consume_exact, consume_sized_int};
+use std::io::Cursor;
+
+pub const VOUCHER_TAG: &[u8] = b"alkane-factory/voucher";
+
+/// A claim authorized off-chain by the distributor key, encoded as
+/// `script_len:u16 | script_pubkey | amount:u128 | nonce:u128 | expiry:u64 | signature:[u8; 64]`
+/// with all integers little-endian. An expiry of 0 never expires.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Voucher {
+    pub script_pubkey: Vec<u8>,
+    pub amount: u128,
+    pub nonce: u128,
+    pub expiry: u64,
+    pub signature: Vec<u8>,
+}
+
+impl Voucher {
+    fn encode_body(&self) -> Result<Vec<u8>> {
+        let script_len: u16 = self
+            .script_pubkey
+            .len()
+            .try_into()
+            .map_err(|_| anyhow!("script_pubkey is too long for a voucher"))?;
+        let mut result: Vec<u8> = script_len.to_le_bytes().to_vec();
+        result.extend(&self.script_pubkey);
+        result.extend(&self.amount.to_le_bytes());
+        result.extend(&self.nonce.to_le_bytes());
+        result.extend(&self.expiry.to_le_bytes());
+        Ok(result)
+    }
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut result = self.encode_body()?;
+        result.extend(&self.signature);
+        Ok(result)
+    }
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<Voucher> {
+        let script_len = consume_sized_int::<u16>(cursor)?;
+        let script_pubkey = consume_exact(cursor, script_len as usize)?;
+        let amount = consume_sized_int::<u128>(cursor)?;
+        let nonce = consume_sized_int::<u128>(cursor)?;
+        let expiry = consume_sized_int::<u64>(cursor)?;
+        let signature = consume_exact(cursor, 64)?;
+        Ok(Voucher {
+            script_pubkey,
+            amount,
+            nonce,
+            expiry,
+            signature,
+        })
+    }
+    /// The digest signed by the distributor key. It commits to the distributor
+    /// alkane so a voucher cannot be replayed against another deployment.
+    pub fn digest(&self, distributor: &AlkaneId) -> Result<[u8; 32]> {
+        let mut message: Vec<u8> = VOUCHER_TAG.to_vec();
+        message.extend(&distributor.block.to_le_bytes());
+        message.extend(&distributor.tx.to_le_bytes());
+        message.extend(&self.encode_body()?);
+        Ok(sha256::Hash::hash(&message).to_byte_array())
+    }
+}
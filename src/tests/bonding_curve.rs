@@ -0,0 +1,171 @@
+use crate::tests::helper::init_factory::{deploy_bonding_curve, deploy_free_mint, spawn_token};
+use crate::tests::helper::wallet::Wallet;
+use alkane_factory_support::constants::ALKANE_FACTORY_BONDING_CURVE_ID;
+use alkane_factory_support::curve::{CURVE_LINEAR, PRICE_SCALE};
+use alkanes::network::set_view_mode;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Spawns a linear curve token priced in `base`, which must be the alkane
+/// spawned just before it so that the token lands on the next sequence number.
+fn spawn_curve(wallet: &mut Wallet, base: &AlkaneId, base_price: u128, factor: u128) -> Result<AlkaneId> {
+    wallet.call(
+        Cellpack {
+            target: AlkaneId {
+                block: 6,
+                tx: ALKANE_FACTORY_BONDING_CURVE_ID,
+            },
+            inputs: vec![0, CURVE_LINEAR, base_price, factor, base.block, base.tx, 0x414243, 0x58595A],
+        },
+        vec![],
+    )?;
+    Ok(AlkaneId {
+        block: 2,
+        tx: base.tx + 1,
+    })
+}
+
+fn reserve(wallet: &Wallet, token: &AlkaneId) -> Result<u128> {
+    let data = wallet.view(token, vec![103])?;
+    assert_eq!(data.len(), 48, "Reserve view should be the base alkane and the reserve");
+    Ok(u128::from_le_bytes(data[32..48].try_into()?))
+}
+
+#[wasm_bindgen_test]
+fn test_bonding_curve_buy_and_sell() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    deploy_bonding_curve(&mut wallet)?;
+    let base = spawn_token(&mut wallet, 10_000)?;
+
+    // 2 base per unit, rising by 1 base for every unit in supply
+    let token = spawn_curve(&mut wallet, &base, 2 * PRICE_SCALE, PRICE_SCALE * PRICE_SCALE)?;
+    assert_eq!(wallet.view_u128(&token, vec![104, 10])?, 65, "10 units should cost 2 * 10 + 0 + 1 + ... + 9");
+
+    // Buying 10 keeps the 65 they cost and refunds the rest
+    wallet.call(
+        Cellpack {
+            target: token.clone(),
+            inputs: vec![77, 10],
+        },
+        vec![(base.clone(), 100)],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 10);
+    assert_eq!(wallet.balance(&base)?, 9_935, "Only the cost should be kept");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 10);
+    assert_eq!(reserve(&wallet, &token)?, 65);
+
+    // Underpaying reverts
+    wallet.call(
+        Cellpack {
+            target: token.clone(),
+            inputs: vec![77, 10],
+        },
+        vec![(base.clone(), 100)],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 10, "A buy costing 165 should revert on 100");
+
+    // Selling the top 4 units refunds 2 * 4 + 6 + 7 + 8 + 9
+    let sell = |min_refund: u128| Cellpack {
+        target: token.clone(),
+        inputs: vec![78, min_refund],
+    };
+    wallet.call(sell(0), vec![(token.clone(), 4)])?;
+    assert_eq!(wallet.balance(&token)?, 6);
+    assert_eq!(wallet.balance(&base)?, 9_973);
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 6, "Sold tokens should be burned");
+    assert_eq!(reserve(&wallet, &token)?, 27);
+
+    // A sale below the minimum refund reverts
+    wallet.call(sell(28), vec![(token.clone(), 6)])?;
+    assert_eq!(wallet.balance(&token)?, 6, "A sale under the minimum refund should revert");
+
+    // Selling everything completes the round trip and empties the reserve
+    wallet.call(sell(27), vec![(token.clone(), 6)])?;
+    assert_eq!(wallet.balance(&token)?, 0);
+    assert_eq!(wallet.balance(&base)?, 10_000, "A buy then sell of everything should break even");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 0);
+    assert_eq!(reserve(&wallet, &token)?, 0);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_bonding_curve_reserve_never_negative() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    deploy_bonding_curve(&mut wallet)?;
+    let base = spawn_token(&mut wallet, 10_000)?;
+
+    // 1 base per PRICE_SCALE units, so small trades round
+    let token = spawn_curve(&mut wallet, &base, 1, 0)?;
+    let buy = Cellpack {
+        target: token.clone(),
+        inputs: vec![77, 3],
+    };
+    wallet.call(buy.clone(), vec![(base.clone(), 1)])?;
+    wallet.call(buy, vec![(base.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 6);
+    assert_eq!(wallet.balance(&base)?, 9_998, "Each buy should round up to 1");
+    assert_eq!(reserve(&wallet, &token)?, 2);
+
+    // Selling back rounds down to nothing, the dust stays in the reserve
+    for _ in 0..2 {
+        wallet.call(
+            Cellpack {
+                target: token.clone(),
+                inputs: vec![78],
+            },
+            vec![(token.clone(), 3)],
+        )?;
+    }
+    assert_eq!(wallet.balance(&token)?, 0);
+    assert_eq!(wallet.balance(&base)?, 9_998, "Sales should round down to 0");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 0);
+    assert_eq!(reserve(&wallet, &token)?, 2, "The reserve should keep the rounding");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_bonding_curve_rejects_invalid_base() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    deploy_bonding_curve(&mut wallet)?;
+    let base = spawn_token(&mut wallet, 10_000)?;
+
+    // the next spawn lands here, so the second attempt prices the token in itself
+    let next = AlkaneId {
+        block: 2,
+        tx: base.tx + 1,
+    };
+    for invalid in [AlkaneId::default(), next.clone()] {
+        wallet.call(
+            Cellpack {
+                target: AlkaneId {
+                    block: 6,
+                    tx: ALKANE_FACTORY_BONDING_CURVE_ID,
+                },
+                inputs: vec![0, CURVE_LINEAR, 1, 0, invalid.block, invalid.tx, 0x414243, 0x58595A],
+            },
+            vec![],
+        )?;
+        assert!(wallet.view(&next, vec![103]).is_err(), "A curve priced in {:?} should be rejected", invalid);
+    }
+
+    // the rejected spawns leave the sequence number to a valid one
+    let token = spawn_curve(&mut wallet, &base, 1, 0)?;
+    assert_eq!(token, next);
+    assert_eq!(reserve(&wallet, &token)?, 0);
+
+    Ok(())
+}
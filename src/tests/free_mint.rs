@@ -18,6 +18,9 @@ use protorune::message::MessageContextParcel;
 use metashrew_support::index_pointer::KeyValuePointer;
 
 use crate::tests::helper::init_factory;
+use crate::tests::helper::init_factory::{deploy_auth_token_factory, deploy_free_mint, spawn_free_mint, spawn_token};
+use crate::tests::helper::wallet::{bytes32_inputs, payload_witness, payout_script, Wallet};
+use alkane_factory_merkle::{build, Allocation};
 
 #[wasm_bindgen_test]
 fn test_free_mint_deployment() -> Result<()> {
@@ -336,4 +339,501 @@ fn test_free_mint_token_minting() -> Result<()> {
     writeln!(out, "Token successfully minted and verified")?;
     
     Ok(())
-} 
\ No newline at end of file
+} 
+#[wasm_bindgen_test]
+fn test_free_mint_window() -> Result<()> {
+    clear();
+    set_view_mode();
+    let block_height: u32 = 850_000;
+
+    let (contract_block, deployment_ids) = init_factory::init_free_mint_block()?;
+    index_block(&contract_block, block_height)?;
+
+    // Create a token whose minting opens at block_height + 3 and closes at block_height + 5
+    let token_cellpacks: Vec<Cellpack> = [
+        Cellpack {
+            target: AlkaneId {
+                block: deployment_ids.free_mint_factory.block,
+                tx: ALKANE_FACTORY_FREE_MINT_ID,
+            },
+            inputs: vec![
+                0,
+                1000,
+                1000,
+                100,
+                0x414243,
+                0x58595A,
+                u128::from(block_height) + 3,
+                u128::from(block_height) + 5,
+            ],
+        },
+    ]
+    .into();
+    let token_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        [vec![]].into(),
+        token_cellpacks,
+    );
+    index_block(&token_block, block_height + 1)?;
+
+    let expected_token_id = ProtoruneRuneId {
+        block: deployment_ids.free_mint_factory.block,
+        tx: ALKANE_FACTORY_FREE_MINT_ID,
+    };
+    let mut out = stdout();
+
+    // Mint before the window opens, during it, and after it closes
+    for (height, expected) in [
+        (block_height + 2, None),
+        (block_height + 3, Some(1000u128)),
+        (block_height + 5, None),
+    ] {
+        let mint_cellpacks: Vec<Cellpack> = [
+            Cellpack {
+                target: AlkaneId {
+                    block: deployment_ids.free_mint_factory.block,
+                    tx: ALKANE_FACTORY_FREE_MINT_ID,
+                },
+                inputs: vec![77],
+            },
+        ]
+        .into();
+        let mint_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+            [vec![]].into(),
+            mint_cellpacks,
+        );
+        index_block(&mint_block, height)?;
+        let mint_outpoint = OutPoint {
+            txid: mint_block.txdata[mint_block.txdata.len() - 1].compute_txid(),
+            vout: 0,
+        };
+        let mint_sheet = load_sheet(
+            &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+                .OUTPOINT_TO_RUNES
+                .select(&consensus_encode(&mint_outpoint)?),
+        );
+        writeln!(out, "Balances after mint at {}: {:?}", height, mint_sheet)?;
+        assert_eq!(
+            mint_sheet.balances.get(&expected_token_id).cloned(),
+            expected,
+            "Unexpected mint result at height {}",
+            height
+        );
+    }
+
+    // Query the contract for the mint window
+    let mut window_parcel = MessageContextParcel::default();
+    window_parcel.height = u64::from(block_height) + 6;
+    window_parcel.calldata = (Cellpack {
+        target: AlkaneId {
+            block: deployment_ids.free_mint_factory.block,
+            tx: ALKANE_FACTORY_FREE_MINT_ID
+        },
+        inputs: vec![105], // 105 is the opcode to get the mint window
+    })
+    .encipher();
+    let window_bytes = view::simulate_parcel(&window_parcel, u64::MAX)?.0.data;
+    assert_eq!(window_bytes.len(), 16, "Mint window should be two u64 heights");
+    let start = u64::from_le_bytes(window_bytes[0..8].try_into()?);
+    let end = u64::from_le_bytes(window_bytes[8..16].try_into()?);
+    assert_eq!(start, u64::from(block_height) + 3, "Mint start should be set");
+    assert_eq!(end, u64::from(block_height) + 5, "Mint end should be set");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_status_at_cap() -> Result<()> {
+    clear();
+    set_view_mode();
+    let block_height: u32 = 850_000;
+
+    let (contract_block, deployment_ids) = init_factory::init_free_mint_block()?;
+    index_block(&contract_block, block_height)?;
+
+    // Create a token capped at 2 mints of 1000
+    let token_cellpacks: Vec<Cellpack> = [
+        Cellpack {
+            target: AlkaneId {
+                block: deployment_ids.free_mint_factory.block,
+                tx: ALKANE_FACTORY_FREE_MINT_ID,
+            },
+            inputs: vec![0, 1000, 1000, 2, 0x414243, 0x58595A],
+        },
+    ]
+    .into();
+    let token_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        [vec![]].into(),
+        token_cellpacks,
+    );
+    index_block(&token_block, block_height + 1)?;
+
+    // Mint three times, the last mint should be rejected up front
+    for i in 0..3u32 {
+        let mint_cellpacks: Vec<Cellpack> = [
+            Cellpack {
+                target: AlkaneId {
+                    block: deployment_ids.free_mint_factory.block,
+                    tx: ALKANE_FACTORY_FREE_MINT_ID,
+                },
+                inputs: vec![77],
+            },
+        ]
+        .into();
+        let mint_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+            [vec![]].into(),
+            mint_cellpacks,
+        );
+        index_block(&mint_block, block_height + 2 + i)?;
+    }
+
+    // Query the contract for its status
+    let mut status_parcel = MessageContextParcel::default();
+    status_parcel.height = u64::from(block_height) + 5;
+    status_parcel.calldata = (Cellpack {
+        target: AlkaneId {
+            block: deployment_ids.free_mint_factory.block,
+            tx: ALKANE_FACTORY_FREE_MINT_ID
+        },
+        inputs: vec![109], // 109 is the opcode to get the mint status
+    })
+    .encipher();
+    let status_bytes = view::simulate_parcel(&status_parcel, u64::MAX)?.0.data;
+
+    let mut out = stdout();
+    writeln!(out, "Status: {:?}", status_bytes)?;
+
+    assert_eq!(status_bytes.len(), 81, "Status should be five u128 values and a flag");
+    let field = |i: usize| u128::from_le_bytes(status_bytes[i * 16..(i + 1) * 16].try_into().unwrap());
+    assert_eq!(field(0), 2, "Minted count should stop at the cap");
+    assert_eq!(field(1), 2, "Cap should be 2");
+    assert_eq!(field(2), 0, "No mints should remain");
+    assert_eq!(field(3), 1000, "Value per mint should be 1000");
+    assert_eq!(field(4), 3000, "Total supply should be the premine plus two mints");
+    assert_eq!(status_bytes[80], 0, "Minting should be closed");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_reinitialization_rejected() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_free_mint(&mut wallet, vec![0, 1000, 1000, 100, 0x414243, 0x58595A])?;
+
+    // Try to initialize again with a new cap, name and symbol and a fresh premine
+    wallet.call(
+        Cellpack {
+            target: token.clone(),
+            inputs: vec![0, 5000, 1, 0, 0x444546, 0x444546],
+        },
+        vec![],
+    )?;
+
+    assert_eq!(wallet.view_u128(&token, vec![102])?, 100, "Cap should not be reset by a second initialize");
+    assert_eq!(
+        wallet.view_u128(&token, vec![104])?,
+        1000,
+        "Value per mint should not be reset by a second initialize"
+    );
+    let name = String::from_utf8(wallet.view(&token, vec![99])?)?;
+    assert_eq!(name, "CBA", "Name should not be reset by a second initialize");
+    let symbol = String::from_utf8(wallet.view(&token, vec![100])?)?;
+    assert_eq!(symbol, "ZYX", "Symbol should not be reset by a second initialize");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 1000, "A second initialize should not mint a fresh premine");
+    assert_eq!(wallet.balance(&token)?, 1000);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_invalid_parameters_rejected() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    let template = deploy_free_mint(&mut wallet)?;
+    let initialize = |inputs: Vec<u128>| Cellpack {
+        target: template.clone(),
+        inputs,
+    };
+
+    // Zero value per mint, a premine above cap * value_per_mint, and an empty name
+    wallet.call(initialize(vec![0, 1000, 0, 100, 0x414243, 0x58595A]), vec![])?;
+    wallet.call(initialize(vec![0, 1001, 10, 100, 0x414243, 0x58595A]), vec![])?;
+    wallet.call(initialize(vec![0, 1000, 1000, 100, 0, 0x58595A]), vec![])?;
+    assert_eq!(wallet.view_u128(&template, vec![101])?, 0, "Rejected initializations should not mint");
+    assert!(wallet.find_new(&[]).is_err(), "Rejected initializations should pay nothing");
+
+    // A rejected initialize must not consume the initialization guard
+    wallet.call(initialize(vec![0, 1000, 1000, 100, 0x414243, 0x58595A]), vec![])?;
+    assert_eq!(wallet.view_u128(&template, vec![102])?, 100, "A valid initialize should succeed after rejected ones");
+    assert_eq!(wallet.balance(&template)?, 1000);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_rate_limits() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+
+    // 1000 per mint, no cap or window, at most 2 mints per transaction and 3 per block
+    let token = spawn_free_mint(&mut wallet, vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 2, 3])?;
+    let mint = |inputs: Vec<u128>| Cellpack {
+        target: token.clone(),
+        inputs,
+    };
+
+    // Three single mints from one transaction, the third is over the per-tx limit
+    wallet.call_in_tx(vec![mint(vec![77]), mint(vec![77]), mint(vec![77])])?;
+    assert_eq!(wallet.balance(&token)?, 2001, "Only 2 mints should fit in one transaction");
+
+    // Three transactions asking for 2 each in one block: 2, then the 1 left, then nothing
+    wallet.call_in_block(vec![mint(vec![78, 2]), mint(vec![78, 2]), mint(vec![78, 2])])?;
+    assert_eq!(wallet.balance(&token)?, 5001, "Only 3 mints should fit in one block");
+
+    // Both limits start over in the next block
+    wallet.call_in_tx(vec![mint(vec![78, 2])])?;
+    assert_eq!(wallet.balance(&token)?, 7001, "Limits should reset in a new block and transaction");
+
+    let limits = wallet.view(&token, vec![106])?;
+    assert_eq!(limits.len(), 48, "Limits should be three u128 values");
+    assert_eq!(u128::from_le_bytes(limits[0..16].try_into()?), 2);
+    assert_eq!(u128::from_le_bytes(limits[16..32].try_into()?), 3);
+    assert_eq!(u128::from_le_bytes(limits[32..48].try_into()?), 0, "Nothing has minted at the next height yet");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_price() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+    let payment = spawn_token(&mut wallet, 10_000)?;
+    let unrelated = spawn_token(&mut wallet, 10_000)?;
+
+    // A price without the alkane it is paid in is rejected
+    let known: Vec<AlkaneId> = vec![payment.clone(), unrelated.clone()];
+    wallet.call(
+        Cellpack {
+            target: AlkaneId { block: 6, tx: ALKANE_FACTORY_FREE_MINT_ID },
+            inputs: vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, 0, 0, 100],
+        },
+        vec![],
+    )?;
+    assert!(wallet.find_new(&known).is_err(), "Initialize without a price alkane should revert");
+
+    // 1000 per mint for 100 of the payment token
+    let token = spawn_free_mint(
+        &mut wallet,
+        vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, payment.block, payment.tx, 100],
+    )?;
+    let mint = Cellpack {
+        target: token.clone(),
+        inputs: vec![77],
+    };
+
+    wallet.call(mint.clone(), vec![(payment.clone(), 50)])?;
+    assert_eq!(wallet.balance(&token)?, 1, "An underpaid mint should revert");
+    assert_eq!(wallet.balance(&payment)?, 10_000, "An underpaid mint should refund the payment");
+
+    wallet.call(mint, vec![(payment.clone(), 150), (unrelated.clone(), 7)])?;
+    assert_eq!(wallet.balance(&token)?, 1001);
+    assert_eq!(wallet.balance(&payment)?, 9_900, "Only the price should be kept, the excess refunded");
+    assert_eq!(wallet.balance(&unrelated)?, 10_000, "Unrelated alkanes should be refunded");
+
+    let price = wallet.view(&token, vec![107])?;
+    assert_eq!(price.len(), 64, "Price should be four u128 values");
+    assert_eq!(u128::from_le_bytes(price[32..48].try_into()?), 100, "Price should be 100");
+    assert_eq!(u128::from_le_bytes(price[48..64].try_into()?), 100, "Proceeds should hold one payment");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_batch_partial_fills() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+
+    // Capped at 5 mints: asking for 10 fills the 5 left, then nothing is left to fill
+    let capped = spawn_free_mint(&mut wallet, vec![0, 1, 1000, 5, 0x414243, 0x58595A])?;
+    let batch = |token: &AlkaneId, count: u128| Cellpack {
+        target: token.clone(),
+        inputs: vec![78, count],
+    };
+    wallet.call(batch(&capped, 10), vec![])?;
+    assert_eq!(wallet.balance(&capped)?, 5001, "A batch should stop at the cap");
+    assert_eq!(wallet.view_u128(&capped, vec![103])?, 5);
+    wallet.call(batch(&capped, 1), vec![])?;
+    assert_eq!(wallet.balance(&capped)?, 5001, "A batch at the cap should revert");
+
+    // At most 3 mints per transaction: asking for 10 fills 3
+    let limited = spawn_free_mint(&mut wallet, vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 3, 0])?;
+    wallet.call(batch(&limited, 10), vec![])?;
+    assert_eq!(wallet.balance(&limited)?, 3001, "A batch should stop at the per-transaction limit");
+    assert_eq!(wallet.view_u128(&limited, vec![103])?, 3);
+
+    // Halving every 2 mints: a batch of 5 pays 1000 + 1000 + 500 + 500 + 250
+    let halving = spawn_free_mint(
+        &mut wallet,
+        vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, 0, 0, 0, 1, 2],
+    )?;
+    wallet.call(batch(&halving, 5), vec![])?;
+    assert_eq!(wallet.balance(&halving)?, 3251, "A batch should follow halvings inside it");
+    assert_eq!(wallet.view_u128(&halving, vec![104])?, 250, "The next mint should be in the third era");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_allowlist() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+
+    // The wallet may mint 2 during the allowlist phase, another script 5
+    let p2tr = hex_lit::hex!("5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c").to_vec();
+    let distribution = build(&[
+        Allocation {
+            script_pubkey: payout_script().to_bytes(),
+            amount: 2,
+            asset: None,
+        },
+        Allocation {
+            script_pubkey: p2tr.clone(),
+            amount: 5,
+            asset: None,
+        },
+    ])?;
+    let init = |end: u128| {
+        let mut inputs = vec![0, 1, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, 0, 0, 0, 0, 0, distribution.length as u128];
+        inputs.extend(bytes32_inputs(&distribution.root));
+        inputs.push(end);
+        inputs
+    };
+
+    // An allowlist phase that has already ended is rejected
+    let known: Vec<AlkaneId> = vec![];
+    wallet.call(
+        Cellpack {
+            target: AlkaneId { block: 6, tx: ALKANE_FACTORY_FREE_MINT_ID },
+            inputs: init(u128::from(wallet.height)),
+        },
+        vec![],
+    )?;
+    assert!(wallet.find_new(&known).is_err(), "Initialize with a past allowlist end should revert");
+
+    let end = u128::from(wallet.height) + 10;
+    let token = spawn_free_mint(&mut wallet, init(end))?;
+    let batch = Cellpack {
+        target: token.clone(),
+        inputs: vec![78, 5],
+    };
+
+    wallet.call(batch.clone(), vec![])?;
+    assert_eq!(wallet.balance(&token)?, 1, "A mint without a proof should revert during the phase");
+
+    // Another leaf's proof does not match the wallet's output
+    wallet.call_with_witness(payload_witness(distribution.payload_for(&p2tr)?.clone()), batch.clone(), vec![])?;
+    assert_eq!(wallet.balance(&token)?, 1, "A proof for another script should revert");
+
+    let proof = payload_witness(distribution.payload_for(payout_script().as_bytes())?.clone());
+    wallet.call_with_witness(proof.clone(), batch.clone(), vec![])?;
+    assert_eq!(wallet.balance(&token)?, 2001, "A batch should stop at the allowlist quota");
+    wallet.call_with_witness(proof, batch, vec![])?;
+    assert_eq!(wallet.balance(&token)?, 2001, "A used up quota should revert");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_vesting_premine_counted() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+
+    // A premine of 5000 vesting over the next 10 blocks
+    let start = u128::from(wallet.height);
+    wallet.call(
+        Cellpack {
+            target: AlkaneId { block: 6, tx: ALKANE_FACTORY_FREE_MINT_ID },
+            inputs: vec![0, 5000, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, start, start + 10],
+        },
+        vec![],
+    )?;
+    let auth_token = wallet.find_new(&[])?;
+    let token = AlkaneId {
+        block: auth_token.block,
+        tx: auth_token.tx - 1,
+    };
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 5000, "The locked premine should count in the total supply");
+    assert_eq!(wallet.balance(&token)?, 0, "The premine should stay locked in the contract");
+
+    // Releasing without the auth token reverts
+    wallet.advance(4)?;
+    wallet.call(
+        Cellpack {
+            target: token.clone(),
+            inputs: vec![81],
+        },
+        vec![],
+    )?;
+    let premine = wallet.view(&token, vec![111])?;
+    assert_eq!(premine.len(), 48, "Premine should be two u128 values and two u64 heights");
+    assert_eq!(u128::from_le_bytes(premine[0..16].try_into()?), 5000);
+    assert_eq!(u128::from_le_bytes(premine[16..32].try_into()?), 0, "Nothing should be released without the auth token");
+    assert_eq!(wallet.balance(&token)?, 0);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_vesting_premine_release() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+
+    // Vesting from 4 blocks ago to 6 blocks ahead, so half has vested on the next block
+    let start = u128::from(wallet.height) - 4;
+    wallet.call(
+        Cellpack {
+            target: AlkaneId { block: 6, tx: ALKANE_FACTORY_FREE_MINT_ID },
+            inputs: vec![0, 1000, 1000, 0, 0x414243, 0x58595A, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, start, start + 10],
+        },
+        vec![],
+    )?;
+    // the premine is locked, so the auth token is all the wallet holds, and the
+    // token was created right before it
+    let auth_token = wallet.find_new(&[])?;
+    let token = AlkaneId {
+        block: auth_token.block,
+        tx: auth_token.tx - 1,
+    };
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 1000, "The locked premine should count in the total supply");
+
+    wallet.call(
+        Cellpack {
+            target: token.clone(),
+            inputs: vec![81],
+        },
+        vec![(auth_token.clone(), 1)],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 500, "Half of the premine should be released");
+    assert_eq!(wallet.balance(&auth_token)?, 1, "The auth token should be returned");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 1000, "Releasing should not mint on top of the premine");
+
+    Ok(())
+}
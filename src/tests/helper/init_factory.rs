@@ -1,4 +1,7 @@
-use crate::tests::std::free_mint_build;
+use crate::tests::helper::wallet::{bytes32_inputs, Wallet};
+use crate::tests::std::{
+    bonding_curve_build, free_mint_build, merkle_distributor_build, owned_token_build,
+};
 use alkanes::tests::helpers::{self as alkane_helpers, assert_binary_deployed_to_id};
 use alkanes_support::cellpack::Cellpack;
 use alkanes_support::id::AlkaneId;
@@ -6,7 +9,12 @@ use anyhow::Result;
 use bitcoin::Block;
 use metashrew::stdio::stdout;
 use std::fmt::Write;
-use alkane_factory_support::constants::ALKANE_FACTORY_FREE_MINT_ID;
+use alkanes::precompiled::alkanes_std_auth_token_build;
+use alkanes_support::constants::AUTH_TOKEN_FACTORY_ID;
+use alkane_factory_support::constants::{
+    ALKANE_FACTORY_BONDING_CURVE_ID, ALKANE_FACTORY_FREE_MINT_ID,
+    ALKANE_FACTORY_MERKLE_DISTRIBUTOR_ID, ALKANE_FACTORY_OWNED_TOKEN_ID,
+};
 
 pub struct FreeMintDeploymentIds {
     pub free_mint_factory: AlkaneId,
@@ -14,6 +22,14 @@ pub struct FreeMintDeploymentIds {
 
 pub fn init_free_mint_block() -> Result<(Block, FreeMintDeploymentIds)> {
     let cellpacks: Vec<Cellpack> = [
+        // Auth token factory init, for tokens that deploy an auth token
+        Cellpack {
+            target: AlkaneId {
+                block: 3,
+                tx: AUTH_TOKEN_FACTORY_ID,
+            },
+            inputs: vec![100],
+        },
         // Free mint factory init
         Cellpack {
             target: AlkaneId {
@@ -27,6 +43,7 @@ pub fn init_free_mint_block() -> Result<(Block, FreeMintDeploymentIds)> {
     
     let test_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
         [
+            alkanes_std_auth_token_build::get_bytes(),
             free_mint_build::get_bytes(),
         ]
         .into(),
@@ -55,5 +72,94 @@ pub fn assert_free_mint_deployed(deployment_ids: &FreeMintDeploymentIds) -> Resu
     Ok(())
 }
 
+/// Deploys the free-mint template to `4:ALKANE_FACTORY_FREE_MINT_ID` so that
+/// `spawn_token` can create copies of it.
+pub fn deploy_free_mint(wallet: &mut Wallet) -> Result<AlkaneId> {
+    wallet.deploy(free_mint_build::get_bytes(), ALKANE_FACTORY_FREE_MINT_ID, vec![100])
+}
+
+/// Creates a fresh free-mint token through `6:ALKANE_FACTORY_FREE_MINT_ID`
+/// with its whole `premine` held by the wallet.
+pub fn spawn_token(wallet: &mut Wallet, premine: u128) -> Result<AlkaneId> {
+    spawn_free_mint(wallet, vec![0, premine, 1, 0, 0x544b4e, 0x544b4e])
+}
+
+/// Initializes a copy of the free-mint template with `inputs`, returning its
+/// id. The premine must be nonzero for the new token to be found.
+pub fn spawn_free_mint(wallet: &mut Wallet, inputs: Vec<u128>) -> Result<AlkaneId> {
+    let known: Vec<AlkaneId> = wallet.balances()?.into_iter().map(|(id, _)| id).collect();
+    wallet.call(
+        Cellpack {
+            target: AlkaneId {
+                block: 6,
+                tx: ALKANE_FACTORY_FREE_MINT_ID,
+            },
+            inputs,
+        },
+        vec![],
+    )?;
+    wallet.find_new(&known)
+}
+
+/// Deploys merkle-distributor to `4:ALKANE_FACTORY_MERKLE_DISTRIBUTOR_ID` and
+/// initializes it with `deposits`, the tree and any trailing optional inputs.
+pub fn init_merkle_distributor(
+    wallet: &mut Wallet,
+    deposits: Vec<(AlkaneId, u128)>,
+    length: usize,
+    root: &[u8; 32],
+    options: Vec<u128>,
+) -> Result<AlkaneId> {
+    let distributor = wallet.deploy(
+        merkle_distributor_build::get_bytes(),
+        ALKANE_FACTORY_MERKLE_DISTRIBUTOR_ID,
+        vec![101],
+    )?;
+    let mut inputs = vec![0, length as u128];
+    inputs.extend(bytes32_inputs(root));
+    inputs.extend(options);
+    wallet.call(
+        Cellpack {
+            target: distributor.clone(),
+            inputs,
+        },
+        deposits,
+    )?;
+    Ok(distributor)
+}
+
+/// Deploys the auth token factory, which `deploy_auth_token` spawns auth
+/// tokens from.
+pub fn deploy_auth_token_factory(wallet: &mut Wallet) -> Result<AlkaneId> {
+    wallet.deploy(alkanes_std_auth_token_build::get_bytes(), AUTH_TOKEN_FACTORY_ID, vec![100])
+}
 
+/// Deploys the bonding-curve template to `4:ALKANE_FACTORY_BONDING_CURVE_ID`.
+pub fn deploy_bonding_curve(wallet: &mut Wallet) -> Result<AlkaneId> {
+    wallet.deploy(bonding_curve_build::get_bytes(), ALKANE_FACTORY_BONDING_CURVE_ID, vec![100])
+}
 
+/// Deploys the owned-token template to `4:ALKANE_FACTORY_OWNED_TOKEN_ID`.
+/// The auth token factory has to be deployed for copies to initialize.
+pub fn deploy_owned_token(wallet: &mut Wallet) -> Result<AlkaneId> {
+    wallet.deploy(owned_token_build::get_bytes(), ALKANE_FACTORY_OWNED_TOKEN_ID, vec![100])
+}
+
+/// Initializes a copy of the owned-token template with `inputs`, returning
+/// the token and its auth token.
+pub fn spawn_owned_token(wallet: &mut Wallet, inputs: Vec<u128>) -> Result<(AlkaneId, AlkaneId)> {
+    let mut known: Vec<AlkaneId> = wallet.balances()?.into_iter().map(|(id, _)| id).collect();
+    wallet.call(
+        Cellpack {
+            target: AlkaneId {
+                block: 6,
+                tx: ALKANE_FACTORY_OWNED_TOKEN_ID,
+            },
+            inputs,
+        },
+        vec![],
+    )?;
+    let token = wallet.find_new(&known)?;
+    known.push(token.clone());
+    Ok((token.clone(), wallet.find_new(&known)?))
+}
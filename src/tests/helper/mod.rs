@@ -1 +1,2 @@
-pub mod init_factory; 
\ No newline at end of file
+pub mod init_factory;
+pub mod wallet;
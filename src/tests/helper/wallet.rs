@@ -0,0 +1,291 @@
+use alkanes::indexer::index_block;
+use alkanes::message::AlkaneMessageContext;
+use alkanes::view;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::envelope::RawEnvelope;
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::blockdata::transaction::OutPoint;
+use bitcoin::hashes::Hash;
+use bitcoin::{
+    absolute, transaction::Version, Amount, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
+    Witness,
+};
+use metashrew_support::index_pointer::KeyValuePointer;
+use ordinals::Runestone;
+use protorune::message::{MessageContext, MessageContextParcel};
+use protorune::protostone::Protostones;
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use protorune::{balance_sheet::load_sheet, tables::RuneTable};
+use protorune_support::balance_sheet::ProtoruneRuneId;
+use protorune_support::protostone::{Protostone, ProtostoneEdict};
+use protorune_support::utils::consensus_encode;
+
+/// The P2WPKH script every wallet transaction pays its first output to.
+pub fn payout_script() -> ScriptBuf {
+    ScriptBuf::from_bytes(hex_lit::hex!("0014751e76e8199196d454941c45d1b3a323f1433bd6").to_vec())
+}
+
+/// Splits 32 bytes into the two inputs `shift_bytes32_or_err` reads them back
+/// from: the bytes reversed, then read as two little-endian u128 values.
+pub fn bytes32_inputs(bytes: &[u8]) -> [u128; 2] {
+    let mut reversed = bytes.to_vec();
+    reversed.reverse();
+    [
+        u128::from_le_bytes(reversed[0..16].try_into().unwrap()),
+        u128::from_le_bytes(reversed[16..32].try_into().unwrap()),
+    ]
+}
+
+/// A witness envelope carrying `payload` as is, for contracts reading it with
+/// `find_witness_payload`.
+pub fn payload_witness(payload: Vec<u8>) -> Witness {
+    RawEnvelope::from(payload).to_witness(false)
+}
+
+/// A protostone running `cellpack`, pointing and refunding to the first output.
+pub fn call_protostone(cellpack: Cellpack) -> Protostone {
+    Protostone {
+        burn: None,
+        message: cellpack.encipher(),
+        edicts: vec![],
+        refund: Some(0),
+        pointer: Some(0),
+        from: None,
+        protocol_tag: AlkaneMessageContext::protocol_tag(),
+    }
+}
+
+/// The alkanes held by `outpoint`.
+pub fn balances_at(outpoint: &OutPoint) -> Result<Vec<(AlkaneId, u128)>> {
+    let sheet = load_sheet(
+        &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+            .OUTPOINT_TO_RUNES
+            .select(&consensus_encode(outpoint)?),
+    );
+    Ok(sheet
+        .balances
+        .iter()
+        .map(|(id, value)| {
+            (
+                AlkaneId {
+                    block: id.block,
+                    tx: id.tx,
+                },
+                *value,
+            )
+        })
+        .collect())
+}
+
+pub fn balance_at(outpoint: &OutPoint, id: &AlkaneId) -> Result<u128> {
+    Ok(balances_at(outpoint)?
+        .into_iter()
+        .find(|(held, _)| held == id)
+        .map(|(_, value)| value)
+        .unwrap_or(0))
+}
+
+/// A chain of transactions, each spending the first output of the one before
+/// it. Every protostone points and refunds to that output, so `outpoint`
+/// always holds everything the wallet owns.
+pub struct Wallet {
+    pub outpoint: OutPoint,
+    pub height: u32,
+}
+
+impl Wallet {
+    pub fn new(height: u32) -> Wallet {
+        Wallet {
+            outpoint: OutPoint {
+                txid: Txid::from_byte_array([0u8; 32]),
+                vout: 0,
+            },
+            height,
+        }
+    }
+    /// Indexes a transaction carrying `protostones` in a block of its own at
+    /// the current height, then moves to the next height.
+    pub fn send(&mut self, witness: Witness, protostones: Vec<Protostone>) -> Result<Transaction> {
+        let mut txs = self.send_block(vec![(witness, protostones)])?;
+        Ok(txs.remove(0))
+    }
+    /// Like `send`, with `outputs` placed between the wallet's output and the
+    /// OP_RETURN. Each one moves the protostone vouts up by one.
+    pub fn send_with_outputs(
+        &mut self,
+        witness: Witness,
+        protostones: Vec<Protostone>,
+        outputs: Vec<TxOut>,
+    ) -> Result<Transaction> {
+        let mut block = create_block_with_coinbase_tx(self.height);
+        let tx = self.transaction(witness, protostones, outputs)?;
+        self.outpoint = OutPoint {
+            txid: tx.compute_txid(),
+            vout: 0,
+        };
+        block.txdata.push(tx.clone());
+        index_block(&block, self.height)?;
+        self.height += 1;
+        Ok(tx)
+    }
+    /// Indexes one transaction per entry in a single block at the current
+    /// height, each spending the one before it, then moves to the next height.
+    pub fn send_block(&mut self, txs: Vec<(Witness, Vec<Protostone>)>) -> Result<Vec<Transaction>> {
+        let mut block = create_block_with_coinbase_tx(self.height);
+        let mut sent: Vec<Transaction> = vec![];
+        for (witness, protostones) in txs {
+            let tx = self.transaction(witness, protostones, vec![])?;
+            self.outpoint = OutPoint {
+                txid: tx.compute_txid(),
+                vout: 0,
+            };
+            block.txdata.push(tx.clone());
+            sent.push(tx);
+        }
+        index_block(&block, self.height)?;
+        self.height += 1;
+        Ok(sent)
+    }
+    fn transaction(
+        &self,
+        witness: Witness,
+        protostones: Vec<Protostone>,
+        outputs: Vec<TxOut>,
+    ) -> Result<Transaction> {
+        let runestone = Runestone {
+            edicts: vec![],
+            etching: None,
+            mint: None,
+            pointer: Some(0),
+            protocol: Some(protostones.encipher()?),
+        };
+        let tx = Transaction {
+            version: Version::ONE,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: self.outpoint.clone(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness,
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(546),
+                script_pubkey: payout_script(),
+            }]
+            .into_iter()
+            .chain(outputs)
+            .chain(std::iter::once(TxOut {
+                value: Amount::from_sat(0),
+                script_pubkey: runestone.encipher(),
+            }))
+            .collect(),
+        };
+        Ok(tx)
+    }
+    /// Indexes `blocks` empty blocks, moving the wallet past them.
+    pub fn advance(&mut self, blocks: u32) -> Result<()> {
+        for _ in 0..blocks {
+            index_block(&create_block_with_coinbase_tx(self.height), self.height)?;
+            self.height += 1;
+        }
+        Ok(())
+    }
+    /// Calls `cellpack`, sending it `transfers` out of the wallet.
+    pub fn call(&mut self, cellpack: Cellpack, transfers: Vec<(AlkaneId, u128)>) -> Result<Transaction> {
+        self.call_with_witness(Witness::new(), cellpack, transfers)
+    }
+    pub fn call_with_witness(
+        &mut self,
+        witness: Witness,
+        cellpack: Cellpack,
+        transfers: Vec<(AlkaneId, u128)>,
+    ) -> Result<Transaction> {
+        let mut protostones: Vec<Protostone> = vec![];
+        if transfers.len() != 0 {
+            // the call is the second protostone, behind the first output and the OP_RETURN
+            protostones.push(Protostone {
+                burn: None,
+                message: vec![],
+                edicts: transfers
+                    .into_iter()
+                    .map(|(id, amount)| ProtostoneEdict {
+                        id: ProtoruneRuneId {
+                            block: id.block,
+                            tx: id.tx,
+                        },
+                        amount,
+                        output: 4,
+                    })
+                    .collect(),
+                refund: Some(0),
+                pointer: Some(0),
+                from: None,
+                protocol_tag: AlkaneMessageContext::protocol_tag(),
+            });
+        }
+        protostones.push(call_protostone(cellpack));
+        self.send(witness, protostones)
+    }
+    /// Runs every cellpack from one transaction, one protostone each.
+    pub fn call_in_tx(&mut self, cellpacks: Vec<Cellpack>) -> Result<Transaction> {
+        self.send(Witness::new(), cellpacks.into_iter().map(call_protostone).collect())
+    }
+    /// Runs every cellpack from its own transaction, all in one block.
+    pub fn call_in_block(&mut self, cellpacks: Vec<Cellpack>) -> Result<Vec<Transaction>> {
+        self.send_block(
+            cellpacks
+                .into_iter()
+                .map(|cellpack| (Witness::new(), vec![call_protostone(cellpack)]))
+                .collect(),
+        )
+    }
+    /// Deploys `binary` to `4:tx` through `3:tx`, calling it with `inputs`.
+    pub fn deploy(&mut self, binary: Vec<u8>, tx: u128, inputs: Vec<u128>) -> Result<AlkaneId> {
+        self.call_with_witness(
+            RawEnvelope::from(binary).to_gzipped_witness(),
+            Cellpack {
+                target: AlkaneId { block: 3, tx },
+                inputs,
+            },
+            vec![],
+        )?;
+        Ok(AlkaneId { block: 4, tx })
+    }
+    pub fn balances(&self) -> Result<Vec<(AlkaneId, u128)>> {
+        balances_at(&self.outpoint)
+    }
+    pub fn balance(&self, id: &AlkaneId) -> Result<u128> {
+        balance_at(&self.outpoint, id)
+    }
+    /// The oldest alkane held by the wallet that is not in `known`, used to
+    /// find alkanes created at a sequence number. A token spawned together
+    /// with its auth token is found before the auth token.
+    pub fn find_new(&self, known: &[AlkaneId]) -> Result<AlkaneId> {
+        self.balances()?
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|id| !known.contains(id))
+            .min_by_key(|id| (id.block, id.tx))
+            .ok_or_else(|| anyhow!("wallet holds no new alkane"))
+    }
+    /// Simulates a read-only call at the current height.
+    pub fn view(&self, target: &AlkaneId, inputs: Vec<u128>) -> Result<Vec<u8>> {
+        let mut parcel = MessageContextParcel::default();
+        parcel.height = u64::from(self.height);
+        parcel.calldata = (Cellpack {
+            target: target.clone(),
+            inputs,
+        })
+        .encipher();
+        Ok(view::simulate_parcel(&parcel, u64::MAX)?.0.data)
+    }
+    pub fn view_u128(&self, target: &AlkaneId, inputs: Vec<u128>) -> Result<u128> {
+        let data = self.view(target, inputs)?;
+        Ok(u128::from_le_bytes(
+            data.get(0..16)
+                .ok_or_else(|| anyhow!("view returned {} bytes", data.len()))?
+                .try_into()?,
+        ))
+    }
+}
@@ -0,0 +1,140 @@
+use crate::tests::helper::init_factory::{deploy_free_mint, init_merkle_distributor, spawn_token};
+use crate::tests::helper::wallet::{payload_witness, payout_script, Wallet};
+use alkane_factory_merkle::{build, Allocation};
+use alkanes::network::set_view_mode;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use alkane_factory_support::merkle::{verify_multi_payload, verify_payload};
+use anyhow::Result;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn allocations() -> Vec<Allocation> {
+    vec![
+        // P2WPKH
+        Allocation {
+            script_pubkey: hex_lit::hex!("0014751e76e8199196d454941c45d1b3a323f1433bd6").to_vec(),
+            amount: 1000,
+            asset: None,
+        },
+        // P2TR
+        Allocation {
+            script_pubkey: hex_lit::hex!(
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+            )
+            .to_vec(),
+            amount: 2500,
+            asset: None,
+        },
+        // P2SH
+        Allocation {
+            script_pubkey: hex_lit::hex!("a914748284390f9e263a4b766a75d0633c50426eb87587").to_vec(),
+            amount: 7,
+            asset: Some(AlkaneId { block: 2, tx: 1 }),
+        },
+    ]
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_payloads_verify() -> Result<()> {
+    let allocations = allocations();
+    let distribution = build(&allocations)?;
+    assert_eq!(distribution.length, allocations.len());
+    for (i, allocation) in allocations.iter().enumerate() {
+        let payload = distribution.payload_for(&allocation.script_pubkey)?;
+        let leaf = verify_payload(payload.clone(), distribution.root, distribution.length)?;
+        assert_eq!(leaf.index as usize, i);
+        assert_eq!(leaf.amount, allocation.amount);
+        assert_eq!(leaf.script_pubkey, allocation.script_pubkey);
+        assert_eq!(leaf.asset, allocation.asset);
+    }
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_rejects_tampered_payload() -> Result<()> {
+    let distribution = build(&allocations())?;
+    let mut payload = distribution.payloads[1].clone();
+    // the amount immediately follows the version byte and the u32 index
+    payload[5] ^= 0x01;
+    assert!(verify_payload(payload, distribution.root, distribution.length).is_err());
+    let mut root = distribution.root;
+    root[0] ^= 0x01;
+    assert!(verify_payload(distribution.payloads[0].clone(), root, distribution.length).is_err());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_single_allocation() -> Result<()> {
+    let allocations = vec![allocations().remove(0)];
+    let distribution = build(&allocations)?;
+    let leaf = verify_payload(
+        distribution.payloads[0].clone(),
+        distribution.root,
+        distribution.length,
+    )?;
+    assert_eq!(leaf.amount, 1000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_multi_payload_verifies() -> Result<()> {
+    let allocations = allocations();
+    let distribution = build(&allocations)?;
+    let leaves = verify_multi_payload(
+        distribution.multi_payload(&[2, 0])?,
+        distribution.root,
+        distribution.length,
+    )?;
+    assert_eq!(leaves.len(), 2);
+    assert_eq!(leaves[0].script_pubkey, allocations[0].script_pubkey);
+    assert_eq!(leaves[1].script_pubkey, allocations[2].script_pubkey);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_duplicate_script() -> Result<()> {
+    let mut allocations = allocations();
+    allocations.push(Allocation {
+        amount: 42,
+        ..allocations[0].clone()
+    });
+    let distribution = build(&allocations)?;
+    assert!(distribution.payload_for(&allocations[0].script_pubkey).is_err());
+    let payloads = distribution.payloads_for(&allocations[0].script_pubkey);
+    assert_eq!(payloads.len(), 2);
+    assert_eq!(verify_payload(payloads[1].clone(), distribution.root, distribution.length)?.amount, 42);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_builder_payload_claims_on_chain() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    // the first allocation pays the wallet's own output
+    let allocations = allocations();
+    assert_eq!(allocations[0].script_pubkey, payout_script().to_bytes());
+    let distribution = build(&allocations)?;
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 3500)],
+        distribution.length,
+        &distribution.root,
+        vec![],
+    )?;
+    wallet.call_with_witness(
+        payload_witness(distribution.payload_for(payout_script().as_bytes())?.clone()),
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![1],
+        },
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 7500, "The on-chain verifier should accept the builder payload");
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![1]);
+    Ok(())
+}
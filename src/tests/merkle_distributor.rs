@@ -0,0 +1,510 @@
+use crate::tests::helper::init_factory::{
+    deploy_auth_token_factory, deploy_free_mint, init_merkle_distributor, spawn_token,
+};
+use crate::tests::helper::wallet::{
+    balance_at, bytes32_inputs, call_protostone, payload_witness, payout_script, Wallet,
+};
+use alkane_factory_merkle::{build, Allocation};
+use alkane_factory_support::protostone::{CallMessage, MessageError};
+use alkanes::message::AlkaneMessageContext;
+use alkanes::network::set_view_mode;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::blockdata::transaction::OutPoint;
+use bitcoin::{Amount, ScriptBuf, TxOut};
+use protorune::message::MessageContext;
+use protorune_support::balance_sheet::ProtoruneRuneId;
+use protorune_support::protostone::{Protostone, ProtostoneEdict};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn allocations() -> Vec<Allocation> {
+    vec![
+        Allocation {
+            script_pubkey: payout_script().to_bytes(),
+            amount: 1000,
+            asset: None,
+        },
+        // P2TR
+        Allocation {
+            script_pubkey: hex_lit::hex!(
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+            )
+            .to_vec(),
+            amount: 500,
+            asset: None,
+        },
+    ]
+}
+
+/// A protostone paying `token` out by `(amount, output)` edicts.
+fn payout_protostone(token: &AlkaneId, edicts: &[(u128, u128)]) -> Protostone {
+    Protostone {
+        burn: None,
+        message: vec![],
+        edicts: edicts
+            .iter()
+            .map(|(amount, output)| ProtostoneEdict {
+                id: ProtoruneRuneId {
+                    block: token.block,
+                    tx: token.tx,
+                },
+                amount: *amount,
+                output: *output,
+            })
+            .collect(),
+        refund: Some(0),
+        pointer: Some(0),
+        from: None,
+        protocol_tag: AlkaneMessageContext::protocol_tag(),
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_claim_once() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let distribution = build(&allocations())?;
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1500)],
+        distribution.length,
+        &distribution.root,
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "Deposit should be locked in the distributor");
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![0], "Leaf 0 should start unclaimed");
+
+    let claim = Cellpack {
+        target: distributor.clone(),
+        inputs: vec![1],
+    };
+    wallet.call_with_witness(payload_witness(distribution.payloads[0].clone()), claim.clone(), vec![])?;
+    assert_eq!(wallet.balance(&token)?, 9500, "Claim should pay the leaf amount to the pointer output");
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![1], "Leaf 0 should be marked claimed");
+
+    // Claiming the same index again reverts and pays nothing
+    wallet.call_with_witness(payload_witness(distribution.payloads[0].clone()), claim, vec![])?;
+    assert_eq!(wallet.balance(&token)?, 9500, "A second claim of the same leaf should revert");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 500, "Only the unclaimed leaf should remain");
+    assert_eq!(wallet.view(&distributor, vec![100, 1])?, vec![0], "Leaf 1 should still be unclaimed");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_rejects_vesting_past_deadline() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let distribution = build(&allocations())?;
+    // deadline at 850_100 but vesting runs until 850_200
+    init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1500)],
+        distribution.length,
+        &distribution.root,
+        vec![850_100, 1, 850_000, 850_200],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 10_000, "Initialize should revert and refund the deposit");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_epoch_budgets() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let distribution = build(&allocations())?;
+    // epoch 0 only funds the P2TR leaf, with 1 unit of auth token to add epochs
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 500)],
+        distribution.length,
+        &distribution.root,
+        vec![0, 1],
+    )?;
+    let auth_token = wallet.find_new(&[token.clone()])?;
+
+    let mut add_epoch = vec![4, distribution.length as u128];
+    add_epoch.extend(bytes32_inputs(&distribution.root));
+    let add_epoch = Cellpack {
+        target: distributor.clone(),
+        inputs: add_epoch,
+    };
+    wallet.call(add_epoch.clone(), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.view(&distributor, vec![106])?, 1u32.to_le_bytes().to_vec(), "An epoch without a deposit should be rejected");
+    assert_eq!(wallet.balance(&auth_token)?, 1, "The auth token should be refunded");
+
+    wallet.call(add_epoch, vec![(auth_token.clone(), 1), (token.clone(), 1000)])?;
+    assert_eq!(wallet.view(&distributor, vec![106])?, 2u32.to_le_bytes().to_vec());
+    assert_eq!(wallet.view_u128(&distributor, vec![108, 1])?, 1000, "The top-up should fund the new epoch");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 1500);
+
+    // epoch 0 cannot pay its 1000 leaf out of epoch 1's deposit
+    let payload = payload_witness(distribution.payloads[0].clone());
+    wallet.call_with_witness(
+        payload.clone(),
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![1, 0],
+        },
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A claim over its epoch's budget should revert");
+
+    wallet.call_with_witness(
+        payload,
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![1, 1],
+        },
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 9500);
+    assert_eq!(wallet.view_u128(&distributor, vec![108, 1])?, 0);
+    assert_eq!(wallet.view_u128(&distributor, vec![108, 0])?, 500, "Epoch 0 should keep its own budget");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_sweep() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+    let other = spawn_token(&mut wallet, 10_000)?;
+
+    let distribution = build(&allocations())?;
+    let deposits = vec![(token.clone(), 1500), (other.clone(), 300)];
+    let deadline = u128::from(wallet.height) + 5;
+
+    // A deadline without auth token units could never be swept
+    init_merkle_distributor(&mut wallet, deposits.clone(), distribution.length, &distribution.root, vec![deadline, 0])?;
+    assert_eq!(wallet.balance(&token)?, 10_000, "A deadline without an owner should be rejected");
+
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        deposits,
+        distribution.length,
+        &distribution.root,
+        vec![deadline, 1],
+    )?;
+    let auth_token = wallet.find_new(&[token.clone(), other.clone()])?;
+    assert_eq!(wallet.balance(&token)?, 8500);
+    assert_eq!(wallet.balance(&other)?, 9700);
+
+    let sweep = Cellpack {
+        target: distributor.clone(),
+        inputs: vec![2],
+    };
+    wallet.call(sweep.clone(), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 8500, "Sweeping before the deadline should revert");
+
+    wallet.advance(deadline as u32 - wallet.height)?;
+    wallet.call_with_witness(
+        payload_witness(distribution.payloads[0].clone()),
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![1],
+        },
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "Claiming after the deadline should revert");
+
+    wallet.call(sweep.clone(), vec![])?;
+    assert_eq!(wallet.balance(&token)?, 8500, "Sweeping without the auth token should revert");
+
+    wallet.call(sweep, vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 10_000, "The sweep should return every asset");
+    assert_eq!(wallet.balance(&other)?, 10_000);
+    assert_eq!(wallet.balance(&auth_token)?, 1, "The auth token should be returned");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 0);
+    assert_eq!(wallet.view_u128(&distributor, vec![102, other.block, other.tx])?, 0);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_vesting_claims() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    // the 1000 leaf vests linearly over 10 blocks
+    let start = u128::from(wallet.height);
+    let distribution = build(&allocations())?;
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1500)],
+        distribution.length,
+        &distribution.root,
+        vec![0, 0, start, start + 10],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500);
+
+    let claim_at = |wallet: &mut Wallet, height: u128| -> Result<()> {
+        wallet.advance(height as u32 - wallet.height)?;
+        wallet.call_with_witness(
+            payload_witness(distribution.payloads[0].clone()),
+            Cellpack {
+                target: distributor.clone(),
+                inputs: vec![1],
+            },
+            vec![],
+        )?;
+        Ok(())
+    };
+
+    // halfway through, half has vested
+    claim_at(&mut wallet, start + 5)?;
+    assert_eq!(wallet.balance(&token)?, 9000, "Half of the leaf should be released");
+    assert_eq!(wallet.view_u128(&distributor, vec![103, 0])?, 500);
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![0], "A partly paid leaf should stay unclaimed");
+
+    // the same proof later releases only what vested since
+    claim_at(&mut wallet, start + 8)?;
+    assert_eq!(wallet.balance(&token)?, 9300, "Only the newly vested part should be released");
+    assert_eq!(wallet.view_u128(&distributor, vec![103, 0])?, 800);
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![0]);
+
+    // after the end the rest is released and the leaf is closed
+    claim_at(&mut wallet, start + 12)?;
+    assert_eq!(wallet.balance(&token)?, 9500, "The remainder should be released after the end");
+    assert_eq!(wallet.view_u128(&distributor, vec![103, 0])?, 1000);
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![1], "A fully paid leaf should be marked claimed");
+
+    claim_at(&mut wallet, start + 13)?;
+    assert_eq!(wallet.balance(&token)?, 9500, "A fully paid leaf should not pay again");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_batch_claim() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let allocations = allocations();
+    let distribution = build(&allocations)?;
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1500)],
+        distribution.length,
+        &distribution.root,
+        vec![],
+    )?;
+    let witness = payload_witness(distribution.multi_payload(&[0, 1])?);
+    // the second leaf is paid to an output of its own, after the wallet's
+    let outputs = vec![TxOut {
+        value: Amount::from_sat(546),
+        script_pubkey: ScriptBuf::from_bytes(allocations[1].script_pubkey.clone()),
+    }];
+    // three outputs, so protostone i sits at vout 4 + i
+    let batch = |pointer: u32| {
+        let mut protostone = call_protostone(Cellpack {
+            target: distributor.clone(),
+            inputs: vec![3],
+        });
+        protostone.pointer = Some(pointer);
+        protostone
+    };
+
+    // one edict for two leaves
+    wallet.send_with_outputs(
+        witness.clone(),
+        vec![batch(5), payout_protostone(&token, &[(1000, 0)])],
+        outputs.clone(),
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A batch with too few edicts should revert");
+
+    // both edicts pay the wallet, so nothing pays the P2TR leaf
+    wallet.send_with_outputs(
+        witness.clone(),
+        vec![batch(5), payout_protostone(&token, &[(1000, 0), (500, 0)])],
+        outputs.clone(),
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A batch paying the wrong script should revert");
+
+    // the payout protostone comes after an empty one the call points back at
+    wallet.send_with_outputs(
+        witness.clone(),
+        vec![
+            payout_protostone(&token, &[]),
+            batch(4),
+            payout_protostone(&token, &[(1000, 0)]),
+        ],
+        outputs.clone(),
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A batch pointing at an earlier protostone should revert");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 1500, "Reverted batches should pay nothing");
+
+    let tx = wallet.send_with_outputs(
+        witness,
+        vec![batch(5), payout_protostone(&token, &[(1000, 0), (500, 1)])],
+        outputs,
+    )?;
+    assert_eq!(wallet.balance(&token)?, 9500, "The wallet's leaf should be paid to its output");
+    assert_eq!(
+        balance_at(
+            &OutPoint {
+                txid: tx.compute_txid(),
+                vout: 1,
+            },
+            &token,
+        )?,
+        500,
+        "The P2TR leaf should be paid to its output"
+    );
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![1], "Leaf 0 should be marked claimed");
+    assert_eq!(wallet.view(&distributor, vec![100, 1])?, vec![1], "Leaf 1 should be marked claimed");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 0, "Nothing should remain");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_malformed_inputs_revert() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let distribution = build(&allocations())?;
+    init_merkle_distributor(&mut wallet, vec![], distribution.length, &distribution.root, vec![])?;
+    // the rejected initialize leaves the distributor open to a valid one
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1500)],
+        distribution.length,
+        &distribution.root,
+        vec![],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "Initializing without alkanes should revert");
+
+    wallet.call(
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![50],
+        },
+        vec![(token.clone(), 100)],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 8500, "An unknown opcode should revert and refund");
+
+    let claim = Cellpack {
+        target: distributor.clone(),
+        inputs: vec![1],
+    };
+    let witness = payload_witness(distribution.payloads[0].clone());
+    let mut with_edicts = call_protostone(claim.clone());
+    with_edicts.edicts = payout_protostone(&token, &[(100, 0)]).edicts;
+    wallet.send(witness.clone(), vec![with_edicts])?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A claim carrying edicts should revert");
+
+    // two outputs, so the second protostone sits at vout 4
+    let mut at_protomessage = call_protostone(claim);
+    at_protomessage.pointer = Some(4);
+    wallet.send(witness, vec![at_protomessage, payout_protostone(&token, &[])])?;
+    assert_eq!(wallet.balance(&token)?, 8500, "A claim pointing at a protomessage should revert");
+    assert_eq!(wallet.view(&distributor, vec![100, 0])?, vec![0], "Leaf 0 should still be unclaimed");
+
+    let tx = wallet.call_in_tx(vec![Cellpack {
+        target: distributor,
+        inputs: vec![102],
+    }])?;
+    assert!(CallMessage::decode(tx.clone(), 3).is_ok());
+    assert_eq!(
+        CallMessage::decode(tx, 4).err(),
+        Some(MessageError::ProtostoneNotFound(4)),
+        "A vout past the protostones should not decode"
+    );
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_merkle_distributor_multi_asset_claims() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+    let other = spawn_token(&mut wallet, 10_000)?;
+
+    let allocation = |amount: u128, asset: Option<AlkaneId>| Allocation {
+        script_pubkey: payout_script().to_bytes(),
+        amount,
+        asset,
+    };
+    let distribution = build(&[
+        allocation(1000, None),
+        allocation(200, Some(other.clone())),
+        // never deposited
+        allocation(50, Some(AlkaneId { block: 2, tx: 999 })),
+    ])?;
+    let distributor = init_merkle_distributor(
+        &mut wallet,
+        vec![(token.clone(), 1000), (other.clone(), 300)],
+        distribution.length,
+        &distribution.root,
+        vec![],
+    )?;
+    let mut alkanes: Vec<u8> = token.clone().into();
+    alkanes.extend(<AlkaneId as Into<Vec<u8>>>::into(other.clone()));
+    assert_eq!(wallet.view(&distributor, vec![105])?, alkanes, "Both deposits should be listed in order");
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 1000);
+    assert_eq!(wallet.view_u128(&distributor, vec![102, other.block, other.tx])?, 300);
+
+    let claim = |wallet: &mut Wallet, index: usize| -> Result<()> {
+        wallet.call_with_witness(
+            payload_witness(distribution.payloads[index].clone()),
+            Cellpack {
+                target: distributor.clone(),
+                inputs: vec![1],
+            },
+            vec![],
+        )?;
+        Ok(())
+    };
+
+    claim(&mut wallet, 2)?;
+    assert_eq!(wallet.balance(&token)?, 9000, "A leaf in an undeposited alkane should revert");
+    assert_eq!(wallet.balance(&other)?, 9700);
+    assert_eq!(wallet.view(&distributor, vec![100, 2])?, vec![0]);
+
+    claim(&mut wallet, 0)?;
+    assert_eq!(wallet.balance(&token)?, 10_000, "A leaf without an asset should pay the first deposit");
+    assert_eq!(wallet.balance(&other)?, 9700);
+
+    claim(&mut wallet, 1)?;
+    assert_eq!(wallet.balance(&other)?, 9900, "A leaf with an asset should pay that asset");
+    assert_eq!(wallet.balance(&token)?, 10_000);
+
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 0);
+    assert_eq!(wallet.view_u128(&distributor, vec![102, other.block, other.tx])?, 100);
+
+    Ok(())
+}
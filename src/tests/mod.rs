@@ -2,4 +2,9 @@ pub mod std;
 pub mod factory;
 pub mod player1_build;
 pub mod free_mint;
+pub mod bonding_curve;
+pub mod merkle_builder;
+pub mod merkle_distributor;
+pub mod owned_token;
+pub mod voucher_distributor;
 pub mod helper;
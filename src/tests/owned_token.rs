@@ -0,0 +1,227 @@
+use crate::tests::helper::init_factory::{
+    deploy_auth_token_factory, deploy_free_mint, deploy_owned_token, spawn_owned_token, spawn_token,
+};
+use crate::tests::helper::wallet::{payload_witness, Wallet};
+use alkanes::network::set_view_mode;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::gz::compress;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// A wallet with the auth token factory, free-mint and owned-token templates
+/// deployed.
+fn setup() -> Result<Wallet> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_auth_token_factory(&mut wallet)?;
+    deploy_free_mint(&mut wallet)?;
+    deploy_owned_token(&mut wallet)?;
+    Ok(wallet)
+}
+
+fn call(token: &AlkaneId, inputs: Vec<u128>) -> Cellpack {
+    Cellpack {
+        target: token.clone(),
+        inputs,
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_renounce() -> Result<()> {
+    let mut wallet = setup()?;
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A])?;
+
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "The owner should be able to mint");
+
+    wallet.call(call(&token, vec![78]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.view(&token, vec![102])?[32], 1, "Minting should be renounced");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "Minting after renounce should revert");
+    assert_eq!(wallet.balance(&auth_token)?, 1, "The auth token should be refunded");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_transfer_ownership() -> Result<()> {
+    let mut wallet = setup()?;
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A])?;
+    let new_owner = spawn_token(&mut wallet, 1)?;
+
+    wallet.call(
+        call(&token, vec![79, new_owner.block, new_owner.tx]),
+        vec![(auth_token.clone(), 1)],
+    )?;
+    let owner = wallet.view(&token, vec![102])?;
+    assert_eq!(u128::from_le_bytes(owner[0..16].try_into()?), new_owner.block);
+    assert_eq!(u128::from_le_bytes(owner[16..32].try_into()?), new_owner.tx);
+    assert_eq!(u128::from_le_bytes(owner[33..49].try_into()?), 0, "The auth token should stay in control");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "The old auth token should be rejected");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(new_owner.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "The new owner should be able to mint");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_max_supply() -> Result<()> {
+    let mut wallet = setup()?;
+    // 1000 up front with a max supply of 1500
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A, 1500])?;
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 1000, "Total supply should include the initial units");
+    assert_eq!(wallet.view_u128(&token, vec![103])?, 1500);
+
+    wallet.call(call(&token, vec![77, 501]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "A mint past the max supply should revert");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "A mint up to the max supply should succeed");
+    assert_eq!(wallet.view_u128(&token, vec![101])?, 1500);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_freeze() -> Result<()> {
+    let mut wallet = setup()?;
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A])?;
+
+    let data = b"updated data".to_vec();
+    wallet.call_with_witness(
+        payload_witness(compress(data.clone())?),
+        call(&token, vec![80]),
+        vec![(auth_token.clone(), 1)],
+    )?;
+    assert_eq!(wallet.view(&token, vec![1000])?, data, "The owner should be able to update data");
+    wallet.call(call(&token, vec![81, 0x444546, 0x474849]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(String::from_utf8(wallet.view(&token, vec![99])?)?, "FED");
+    assert_eq!(String::from_utf8(wallet.view(&token, vec![100])?)?, "IHG");
+
+    wallet.call(call(&token, vec![82]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.view(&token, vec![104])?, vec![1], "Metadata should be frozen");
+
+    wallet.call_with_witness(
+        payload_witness(compress(b"frozen data".to_vec())?),
+        call(&token, vec![80]),
+        vec![(auth_token.clone(), 1)],
+    )?;
+    assert_eq!(wallet.view(&token, vec![1000])?, data, "Updating data after freeze should revert");
+    wallet.call(call(&token, vec![81, 0x414243, 0x58595A]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(String::from_utf8(wallet.view(&token, vec![99])?)?, "FED", "Renaming after freeze should revert");
+    assert_eq!(String::from_utf8(wallet.view(&token, vec![100])?)?, "IHG");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_multisig() -> Result<()> {
+    let mut wallet = setup()?;
+    let signers = [
+        spawn_token(&mut wallet, 1)?,
+        spawn_token(&mut wallet, 1)?,
+        spawn_token(&mut wallet, 1)?,
+    ];
+    // 2 of 3 signers, no max supply
+    let mut inputs = vec![0, 1, 1000, 0x414243, 0x58595A, 0, 2, 3];
+    for signer in signers.iter() {
+        inputs.extend([signer.block, signer.tx]);
+    }
+    let (token, auth_token) = spawn_owned_token(&mut wallet, inputs)?;
+    let owner = wallet.view(&token, vec![102])?;
+    assert_eq!(owner.len(), 49, "Owner should be the auth token, the renounced flag and the threshold");
+    assert_eq!(u128::from_le_bytes(owner[33..49].try_into()?), 2, "The owner view should show signers in control");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(signers[0].clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "One of two required signers should be rejected");
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "The auth token alone should be rejected");
+
+    wallet.call(call(&token, vec![77, 500]), vec![(signers[0].clone(), 1), (signers[2].clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "Two signers should be able to mint");
+    for signer in signers.iter() {
+        assert_eq!(wallet.balance(signer)?, 1, "Signers should be refunded");
+    }
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_timelocked_mint() -> Result<()> {
+    let mut wallet = setup()?;
+    // no max supply or signers, owner mints wait 5 blocks
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A, 0, 0, 5])?;
+
+    wallet.call(call(&token, vec![77, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "A direct mint should revert while timelocked");
+
+    let announced_at = u64::from(wallet.height);
+    wallet.call(call(&token, vec![83, 500]), vec![(auth_token.clone(), 1)])?;
+    let pending = wallet.view(&token, vec![106])?;
+    assert_eq!(u128::from_le_bytes(pending[0..16].try_into()?), 500);
+    assert_eq!(u64::from_le_bytes(pending[16..24].try_into()?), announced_at + 5);
+
+    wallet.call(call(&token, vec![84]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "Executing before the pending height should revert");
+
+    wallet.advance((announced_at + 5) as u32 - wallet.height)?;
+    wallet.call(call(&token, vec![84]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1500, "Executing at the pending height should mint");
+    assert_eq!(u128::from_le_bytes(wallet.view(&token, vec![106])?[0..16].try_into()?), 0);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_announce_respects_max_supply() -> Result<()> {
+    let mut wallet = setup()?;
+    // 1000 up front with a max supply of 1500, owner mints wait 5 blocks
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A, 1500, 0, 5])?;
+
+    wallet.call(call(&token, vec![83, 501]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(
+        u128::from_le_bytes(wallet.view(&token, vec![106])?[0..16].try_into()?),
+        0,
+        "Announcing a mint past the max supply should revert"
+    );
+
+    wallet.call(call(&token, vec![83, 500]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(u128::from_le_bytes(wallet.view(&token, vec![106])?[0..16].try_into()?), 500);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_owned_token_cancel_and_renounce_drop_pending_mint() -> Result<()> {
+    let mut wallet = setup()?;
+    // no max supply or signers, owner mints wait 5 blocks
+    let (token, auth_token) = spawn_owned_token(&mut wallet, vec![0, 1, 1000, 0x414243, 0x58595A, 0, 0, 5])?;
+    let pending = |wallet: &Wallet| -> Result<u128> {
+        Ok(u128::from_le_bytes(wallet.view(&token, vec![106])?[0..16].try_into()?))
+    };
+
+    wallet.call(call(&token, vec![83, 500]), vec![(auth_token.clone(), 1)])?;
+    wallet.call(call(&token, vec![85]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(pending(&wallet)?, 0, "Cancelling should drop the announced mint");
+
+    wallet.advance(5)?;
+    wallet.call(call(&token, vec![84]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(wallet.balance(&token)?, 1000, "Executing a cancelled mint should revert");
+
+    wallet.call(call(&token, vec![83, 300]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(pending(&wallet)?, 300, "A new mint can be announced after a cancel");
+
+    wallet.call(call(&token, vec![78]), vec![(auth_token.clone(), 1)])?;
+    assert_eq!(pending(&wallet)?, 0, "Renouncing should drop the announced mint");
+    let pending_height = wallet.view(&token, vec![106])?;
+    assert_eq!(u64::from_le_bytes(pending_height[16..24].try_into()?), 0);
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use crate::tests::helper::init_factory::{deploy_free_mint, spawn_token};
+use crate::tests::helper::wallet::{bytes32_inputs, payload_witness, payout_script, Wallet};
+use crate::tests::std::voucher_distributor_build;
+use alkane_factory_support::constants::ALKANE_FACTORY_VOUCHER_DISTRIBUTOR_ID;
+use alkane_factory_support::voucher::Voucher;
+use alkanes::network::set_view_mode;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::secp256k1::{Keypair, Message, Secp256k1};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn keypair() -> Keypair {
+    Keypair::from_seckey_slice(&Secp256k1::new(), &[0x11; 32]).unwrap()
+}
+
+fn signed(mut voucher: Voucher, distributor: &AlkaneId) -> Result<Voucher> {
+    let digest = voucher.digest(distributor)?;
+    voucher.signature = Secp256k1::new()
+        .sign_schnorr_no_aux_rand(&Message::from_digest(digest), &keypair())
+        .serialize()
+        .to_vec();
+    Ok(voucher)
+}
+
+fn claim(wallet: &mut Wallet, distributor: &AlkaneId, voucher: &Voucher) -> Result<()> {
+    wallet.call_with_witness(
+        payload_witness(voucher.encode()?),
+        Cellpack {
+            target: distributor.clone(),
+            inputs: vec![1],
+        },
+        vec![],
+    )?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_voucher_distributor_claims() -> Result<()> {
+    clear();
+    set_view_mode();
+    let mut wallet = Wallet::new(850_000);
+    deploy_free_mint(&mut wallet)?;
+    let token = spawn_token(&mut wallet, 10_000)?;
+
+    let distributor = wallet.deploy(
+        voucher_distributor_build::get_bytes(),
+        ALKANE_FACTORY_VOUCHER_DISTRIBUTOR_ID,
+        vec![102],
+    )?;
+    let signer = keypair().x_only_public_key().0.serialize();
+    let mut init = vec![0];
+    init.extend(bytes32_inputs(&signer));
+    wallet.call(
+        Cellpack {
+            target: distributor.clone(),
+            inputs: init,
+        },
+        vec![(token.clone(), 5000)],
+    )?;
+    assert_eq!(wallet.balance(&token)?, 5000, "Deposit should be locked in the distributor");
+    // pins the byte order of the signer inputs read by shift_bytes32_or_err
+    assert_eq!(wallet.view(&distributor, vec![101])?, signer.to_vec(), "Signer should round trip");
+
+    let voucher = signed(
+        Voucher {
+            script_pubkey: payout_script().to_bytes(),
+            amount: 100,
+            nonce: 1,
+            expiry: 0,
+            signature: vec![],
+        },
+        &distributor,
+    )?;
+    claim(&mut wallet, &distributor, &voucher)?;
+    assert_eq!(wallet.balance(&token)?, 5100, "A valid voucher should pay out");
+    assert_eq!(wallet.view(&distributor, vec![100, 1])?, vec![1], "Nonce 1 should be used");
+
+    // Reusing the nonce
+    claim(&mut wallet, &distributor, &voucher)?;
+    assert_eq!(wallet.balance(&token)?, 5100, "A reused nonce should be rejected");
+
+    // Expiring at the height it is claimed at
+    let expired = signed(
+        Voucher {
+            nonce: 2,
+            expiry: u64::from(wallet.height),
+            ..voucher.clone()
+        },
+        &distributor,
+    )?;
+    claim(&mut wallet, &distributor, &expired)?;
+    assert_eq!(wallet.balance(&token)?, 5100, "An expired voucher should be rejected");
+
+    // Signed for another distributor
+    let foreign = signed(
+        Voucher {
+            nonce: 3,
+            ..voucher.clone()
+        },
+        &AlkaneId {
+            block: 4,
+            tx: ALKANE_FACTORY_VOUCHER_DISTRIBUTOR_ID + 1,
+        },
+    )?;
+    claim(&mut wallet, &distributor, &foreign)?;
+    assert_eq!(wallet.balance(&token)?, 5100, "A voucher for another distributor should be rejected");
+
+    // Paying a scriptPubKey other than the pointer output
+    let misdirected = signed(
+        Voucher {
+            script_pubkey: hex_lit::hex!(
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+            )
+            .to_vec(),
+            nonce: 4,
+            ..voucher.clone()
+        },
+        &distributor,
+    )?;
+    claim(&mut wallet, &distributor, &misdirected)?;
+    assert_eq!(wallet.balance(&token)?, 5100, "A voucher for another scriptPubKey should be rejected");
+
+    for nonce in 2..=4u128 {
+        assert_eq!(wallet.view(&distributor, vec![100, nonce])?, vec![0], "Rejected nonces stay unused");
+    }
+    assert_eq!(wallet.view_u128(&distributor, vec![102])?, 4900, "Only the valid claim is deducted");
+
+    Ok(())
+}